@@ -0,0 +1,24 @@
+//! Error types
+
+use solana_sdk::{program_error::ProgramError, pubkey::Pubkey, signer::SignerError};
+use thiserror::Error;
+
+/// Errors that may be returned by the client.
+#[derive(Debug, Eq, Error, PartialEq)]
+pub enum ClientError {
+    /// RpcFailed
+    #[error("RPC request failed: {0}")]
+    RpcFailed(String),
+    /// Program
+    #[error("Program error: {0}")]
+    Program(#[from] ProgramError),
+    /// NotRegistered
+    #[error("Mint {0} is not registered")]
+    NotRegistered(Pubkey),
+    /// NotInitialized
+    #[error("Registry is not initialized")]
+    NotInitialized,
+    /// Signer
+    #[error("Signing failed: {0}")]
+    Signer(#[from] SignerError),
+}
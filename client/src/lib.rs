@@ -0,0 +1,506 @@
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+//! Client of the mint-registry program for Rust backends
+
+pub mod error;
+pub mod mock;
+pub mod rpc;
+
+// Export the program crate for its instruction and state types
+pub use mint_registry;
+
+use crate::{
+    error::ClientError,
+    rpc::{AccountFilter, RegistryRpc},
+};
+use mint_registry::{
+    find_mint_extension_address, find_registry_config_address,
+    instruction::{
+        close_mint_instruction, modify_mint_instruction, register_mint_instruction,
+        update_mint_instruction, MintUpdate,
+    },
+    state::{MintExtension, MintExtensionLayout, MintMetadata, RegistryConfig},
+};
+use solana_sdk::{
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    transaction::Transaction,
+};
+
+// offset of is_initialized in the extension, after authorities, supply and decimals
+const IS_INITIALIZED_OFFSET: usize = 73;
+
+/// A registered MintExtension account, decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Registration {
+    /// address of the MintExtension account
+    pub address: Pubkey,
+    /// the extension
+    pub mint_extension: MintExtension,
+    /// layout the account is stored in
+    pub layout: MintExtensionLayout,
+    /// metadata, empty in layouts without a metadata section
+    pub metadata: MintMetadata,
+}
+impl Registration {
+    /// Decodes the data of a MintExtension account in any layout.
+    pub fn unpack(address: Pubkey, data: &[u8]) -> Result<Self, ProgramError> {
+        let (mint_extension, layout) = MintExtension::unpack_account(data)?;
+        let metadata = MintMetadata::unpack_account(data)?;
+        Ok(Self {
+            address,
+            mint_extension,
+            layout,
+            metadata,
+        })
+    }
+}
+
+/// Reads registrations and sends registry instructions through a
+/// [RegistryRpc](rpc/trait.RegistryRpc.html).
+///
+/// Transactions are paid and signed by the authority alone, so mints whose
+/// authority is a multisig need the instruction builders of
+/// [mint_registry::instruction](../mint_registry/instruction/index.html).
+#[derive(Debug)]
+pub struct RegistryClient<R> {
+    rpc: R,
+    program_id: Pubkey,
+}
+impl<R: RegistryRpc> RegistryClient<R> {
+    /// Creates a client of the registry deployed at program_id.
+    pub fn new(rpc: R, program_id: Pubkey) -> Self {
+        Self { rpc, program_id }
+    }
+
+    /// RPC the client goes through
+    pub fn rpc(&self) -> &R {
+        &self.rpc
+    }
+
+    /// Address of the registry program
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    /// Fetches the registration of a mint, None when it is not registered.
+    pub fn get_registration(&self, mint: &Pubkey) -> Result<Option<Registration>, ClientError> {
+        let (address, _) = find_mint_extension_address(&self.program_id, mint);
+        let account = match self.rpc.get_account(&address)? {
+            Some(account) if account.owner == self.program_id && !account.data.is_empty() => account,
+            _ => return Ok(None),
+        };
+        let registration = Registration::unpack(address, &account.data)?;
+        if !registration.mint_extension.is_initialized || registration.mint_extension.mint != *mint {
+            return Ok(None);
+        }
+        Ok(Some(registration))
+    }
+
+    /// Fetches the RegistryConfig, whose validation policy the transaction
+    /// builders derive symbol indexes with.
+    pub fn get_registry_config(&self) -> Result<RegistryConfig, ClientError> {
+        let (address, _) = find_registry_config_address(&self.program_id);
+        match self.rpc.get_account(&address)? {
+            Some(account) if account.owner == self.program_id => Ok(RegistryConfig::unpack_account(&account.data)?),
+            _ => Err(ClientError::NotInitialized),
+        }
+    }
+
+    /// Lists every registration, with one getProgramAccounts request per
+    /// [layout](../mint_registry/state/enum.MintExtensionLayout.html).
+    ///
+    /// Accounts that cannot be decoded are skipped.
+    pub fn list_registrations(&self) -> Result<Vec<Registration>, ClientError> {
+        let mut registrations = Vec::new();
+        for &layout in &MintExtensionLayout::ALL {
+            let filters = match layout.account_type() {
+                None => vec![
+                    AccountFilter::DataSize(MintExtension::LEN as u64),
+                    AccountFilter::Memcmp { offset: IS_INITIALIZED_OFFSET, bytes: vec![1] },
+                ],
+                Some(account_type) => vec![
+                    AccountFilter::Memcmp { offset: 0, bytes: vec![account_type as u8] },
+                    AccountFilter::Memcmp { offset: 1 + IS_INITIALIZED_OFFSET, bytes: vec![1] },
+                ],
+            };
+            for (address, account) in self.rpc.get_program_accounts(&self.program_id, &filters)? {
+                // untagged accounts may start with the byte of a tag
+                match Registration::unpack(address, &account.data) {
+                    Ok(registration) if registration.layout == layout => registrations.push(registration),
+                    _ => {}
+                }
+            }
+        }
+        Ok(registrations)
+    }
+
+    /// Builds and signs a RegisterMint transaction.
+    pub fn register_mint_transaction(
+        &self,
+        mint: &Pubkey,
+        symbol: String,
+        name: String,
+        authority: &dyn Signer,
+    ) -> Result<Transaction, ClientError> {
+        let config = self.get_registry_config()?;
+        let instruction =
+            register_mint_instruction(&self.program_id, &config.policy, mint, symbol, name, &authority.pubkey(), &[])?;
+        self.sign(instruction, authority)
+    }
+
+    /// Builds and signs a ModifyMint transaction for a registered mint.
+    pub fn modify_mint_transaction(
+        &self,
+        mint: &Pubkey,
+        symbol: String,
+        name: String,
+        authority: &dyn Signer,
+    ) -> Result<Transaction, ClientError> {
+        let config = self.get_registry_config()?;
+        let registration = self.registration(mint)?;
+        let instruction = modify_mint_instruction(
+            &self.program_id,
+            &config.policy,
+            mint,
+            registration.mint_extension.symbol()?,
+            symbol,
+            name,
+            &authority.pubkey(),
+            &[],
+        )?;
+        self.sign(instruction, authority)
+    }
+
+    /// Builds and signs an UpdateMint transaction for a registered mint.
+    pub fn update_mint_transaction(
+        &self,
+        mint: &Pubkey,
+        update: MintUpdate,
+        authority: &dyn Signer,
+    ) -> Result<Transaction, ClientError> {
+        let config = self.get_registry_config()?;
+        let registration = self.registration(mint)?;
+        let instruction = update_mint_instruction(
+            &self.program_id,
+            &config.policy,
+            mint,
+            registration.mint_extension.symbol()?,
+            update,
+            &authority.pubkey(),
+            &[],
+        )?;
+        self.sign(instruction, authority)
+    }
+
+    /// Builds and signs a CloseMint transaction for a registered mint,
+    /// refunding the rent to destination.
+    pub fn close_mint_transaction(
+        &self,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        authority: &dyn Signer,
+    ) -> Result<Transaction, ClientError> {
+        let config = self.get_registry_config()?;
+        let registration = self.registration(mint)?;
+        let instruction = close_mint_instruction(
+            &self.program_id,
+            &config.policy,
+            mint,
+            registration.mint_extension.symbol()?,
+            destination,
+            &authority.pubkey(),
+            &[],
+        )?;
+        self.sign(instruction, authority)
+    }
+
+    /// Registers a mint, see [register_mint_transaction](#method.register_mint_transaction).
+    pub fn register_mint(
+        &self,
+        mint: &Pubkey,
+        symbol: String,
+        name: String,
+        authority: &dyn Signer,
+    ) -> Result<Signature, ClientError> {
+        self.rpc.send_transaction(&self.register_mint_transaction(mint, symbol, name, authority)?)
+    }
+
+    /// Modifies a mint, see [modify_mint_transaction](#method.modify_mint_transaction).
+    pub fn modify_mint(
+        &self,
+        mint: &Pubkey,
+        symbol: String,
+        name: String,
+        authority: &dyn Signer,
+    ) -> Result<Signature, ClientError> {
+        self.rpc.send_transaction(&self.modify_mint_transaction(mint, symbol, name, authority)?)
+    }
+
+    /// Updates a mint, see [update_mint_transaction](#method.update_mint_transaction).
+    pub fn update_mint(
+        &self,
+        mint: &Pubkey,
+        update: MintUpdate,
+        authority: &dyn Signer,
+    ) -> Result<Signature, ClientError> {
+        self.rpc.send_transaction(&self.update_mint_transaction(mint, update, authority)?)
+    }
+
+    /// Closes a mint, see [close_mint_transaction](#method.close_mint_transaction).
+    pub fn close_mint(
+        &self,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        authority: &dyn Signer,
+    ) -> Result<Signature, ClientError> {
+        self.rpc.send_transaction(&self.close_mint_transaction(mint, destination, authority)?)
+    }
+
+    fn registration(&self, mint: &Pubkey) -> Result<Registration, ClientError> {
+        self.get_registration(mint)?
+            .ok_or(ClientError::NotRegistered(*mint))
+    }
+
+    fn sign(&self, instruction: Instruction, authority: &dyn Signer) -> Result<Transaction, ClientError> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&authority.pubkey()));
+        transaction.try_sign(&[authority], blockhash)?;
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockRpc;
+    use mint_registry::{
+        find_symbol_index_address, instruction::RegistryInstruction, state::SymbolIndex,
+        validation::{ValidationPolicy, Whitespace},
+    };
+    use solana_sdk::{account::Account, signature::Keypair};
+
+    fn padded(value: &str) -> [u8; mint_registry::state::MAX_SYMBOL_NAME_LEN] {
+        let mut buf = [0; mint_registry::state::MAX_SYMBOL_NAME_LEN];
+        buf[..value.len()].copy_from_slice(value.as_bytes());
+        buf
+    }
+
+    fn new_mint_extension(mint: &Pubkey, authority: &Pubkey, symbol: &str) -> MintExtension {
+        MintExtension {
+            mint_authority: *authority,
+            update_authority: *authority,
+            is_initialized: true,
+            mint: *mint,
+            symbol_len: symbol.len() as u8,
+            symbol: padded(symbol),
+            name_len: 4,
+            name: padded("name"),
+            ..MintExtension::default()
+        }
+    }
+
+    fn add_registration(
+        rpc: &MockRpc,
+        program_id: &Pubkey,
+        mint_extension: &MintExtension,
+        layout: MintExtensionLayout,
+        metadata: &MintMetadata,
+    ) -> Pubkey {
+        let (address, _) = find_mint_extension_address(program_id, &mint_extension.mint);
+        let len = if layout == MintExtensionLayout::LATEST {
+            metadata.account_len()
+        } else {
+            MintExtension::account_len(layout)
+        };
+        let mut account = Account::new(1, len, program_id);
+        mint_extension.pack_account(layout, &mut account.data).unwrap();
+        if layout == MintExtensionLayout::LATEST {
+            metadata.pack_account(&mut account.data).unwrap();
+        }
+        rpc.set_account(address, account);
+        address
+    }
+
+    fn add_registry_config(rpc: &MockRpc, program_id: &Pubkey, policy: ValidationPolicy) {
+        let config = RegistryConfig { policy, ..RegistryConfig::default() };
+        let mut account = Account::new(1, config.account_len(), program_id);
+        config.pack_account(&mut account.data).unwrap();
+        rpc.set_account(find_registry_config_address(program_id).0, account);
+    }
+
+    #[test]
+    fn test_get_registration() {
+        let program_id = Pubkey::new_unique();
+        let client = RegistryClient::new(MockRpc::new(), program_id);
+        let mint = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        assert_eq!(client.get_registration(&mint), Ok(None));
+
+        let mint_extension = new_mint_extension(&mint, &authority, "SYM");
+        let metadata = MintMetadata {
+            website: String::from("https://example.com"),
+            ..MintMetadata::default()
+        };
+        let address = add_registration(client.rpc(), &program_id, &mint_extension, MintExtensionLayout::LATEST, &metadata);
+        assert_eq!(
+            client.get_registration(&mint),
+            Ok(Some(Registration {
+                address,
+                mint_extension,
+                layout: MintExtensionLayout::LATEST,
+                metadata,
+            }))
+        );
+
+        // closed by an earlier version of the program, or handed back to the
+        // system program
+        let closed = MintExtension { is_initialized: false, ..mint_extension };
+        add_registration(client.rpc(), &program_id, &closed, MintExtensionLayout::LATEST, &MintMetadata::default());
+        assert_eq!(client.get_registration(&mint), Ok(None));
+        client.rpc().set_account(address, Account::new(1, 0, &solana_sdk::system_program::id()));
+        assert_eq!(client.get_registration(&mint), Ok(None));
+
+        client.rpc().set_account(address, Account::new(1, 3, &program_id));
+        assert_eq!(client.get_registration(&mint), Err(ProgramError::InvalidAccountData.into()));
+    }
+
+    #[test]
+    fn test_list_registrations() {
+        let program_id = Pubkey::new_unique();
+        let client = RegistryClient::new(MockRpc::new(), program_id);
+        let authority = Pubkey::new_unique();
+        let rpc = client.rpc();
+
+        let mut expected = Vec::new();
+        for &layout in &MintExtensionLayout::ALL {
+            let mint_extension = new_mint_extension(&Pubkey::new_unique(), &authority, "SYM");
+            let address = add_registration(rpc, &program_id, &mint_extension, layout, &MintMetadata::default());
+            expected.push(Registration {
+                address,
+                mint_extension,
+                layout,
+                metadata: MintMetadata::default(),
+            });
+        }
+        // an untagged account whose mint authority starts with a tag byte
+        let mut tagged_authority = [0u8; 32];
+        tagged_authority[0] = MintExtensionLayout::LATEST.account_type().unwrap() as u8;
+        let mint_extension = new_mint_extension(&Pubkey::new_unique(), &Pubkey::new_from_array(tagged_authority), "OLD");
+        let address = add_registration(rpc, &program_id, &mint_extension, MintExtensionLayout::V0, &MintMetadata::default());
+        expected.push(Registration {
+            address,
+            mint_extension,
+            layout: MintExtensionLayout::V0,
+            metadata: MintMetadata::default(),
+        });
+
+        // closed extensions, other accounts of the program and accounts of
+        // other programs are left out
+        let closed = MintExtension { is_initialized: false, ..new_mint_extension(&Pubkey::new_unique(), &authority, "SYM") };
+        add_registration(rpc, &program_id, &closed, MintExtensionLayout::LATEST, &MintMetadata::default());
+        let symbol_index = SymbolIndex { mint: Pubkey::new_unique() };
+        let mut account = Account::new(1, SymbolIndex::LEN, &program_id);
+        symbol_index.pack_account(&mut account.data).unwrap();
+        rpc.set_account(find_symbol_index_address(&program_id, &ValidationPolicy::default(), "SYM").0, account);
+        let other = new_mint_extension(&Pubkey::new_unique(), &authority, "SYM");
+        add_registration(rpc, &Pubkey::new_unique(), &other, MintExtensionLayout::LATEST, &MintMetadata::default());
+
+        let mut registrations = client.list_registrations().unwrap();
+        registrations.sort_by_key(|registration| (registration.layout, registration.address));
+        expected.sort_by_key(|registration| (registration.layout, registration.address));
+        assert_eq!(registrations, expected);
+    }
+
+    #[test]
+    fn test_transactions() {
+        let program_id = Pubkey::new_unique();
+        let client = RegistryClient::new(MockRpc::new(), program_id);
+        let authority = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+
+        assert_eq!(
+            client.register_mint(&mint, String::from("SYM"), String::from("name"), &authority),
+            Err(ClientError::NotInitialized)
+        );
+        add_registry_config(client.rpc(), &program_id, ValidationPolicy::default());
+
+        // only registered mints can be modified or closed
+        assert_eq!(
+            client.modify_mint(&mint, String::from("SYM2"), String::from("name"), &authority),
+            Err(ClientError::NotRegistered(mint))
+        );
+        assert_eq!(
+            client.close_mint(&mint, &destination, &authority),
+            Err(ClientError::NotRegistered(mint))
+        );
+
+        let signature = client.register_mint(&mint, String::from("SYM"), String::from("name"), &authority).unwrap();
+        let transaction = &client.rpc().sent_transactions()[0];
+        assert_eq!(transaction.signatures, vec![signature]);
+        assert_eq!(transaction.message.account_keys[0], authority.pubkey());
+        assert_eq!(
+            transaction.message.instructions[0].data,
+            RegistryInstruction::RegisterMint {
+                mint,
+                symbol: String::from("SYM"),
+                name: String::from("name"),
+            }.pack().unwrap()
+        );
+
+        add_registration(
+            client.rpc(),
+            &program_id,
+            &new_mint_extension(&mint, &authority.pubkey(), "SYM"),
+            MintExtensionLayout::LATEST,
+            &MintMetadata::default(),
+        );
+
+        // the current symbol picks the index to release
+        let transaction = client.modify_mint_transaction(&mint, String::from("SYM2"), String::from("name"), &authority).unwrap();
+        assert_eq!(
+            transaction.message.instructions[0].data,
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint, "SYM", String::from("SYM2"), String::from("name"), &authority.pubkey(), &[]).unwrap().data
+        );
+        let old_symbol_index = find_symbol_index_address(&program_id, &ValidationPolicy::default(), "SYM").0;
+        assert!(transaction.message.account_keys.contains(&old_symbol_index));
+
+        let update = MintUpdate::default().name(String::from("new name"));
+        client.update_mint(&mint, update.clone(), &authority).unwrap();
+        let transaction = client.rpc().sent_transactions().pop().unwrap();
+        assert_eq!(
+            transaction.message.instructions[0].data,
+            RegistryInstruction::UpdateMint { update }.pack().unwrap()
+        );
+
+        client.close_mint(&mint, &destination, &authority).unwrap();
+        let transaction = client.rpc().sent_transactions().pop().unwrap();
+        assert!(transaction.message.account_keys.contains(&destination));
+        assert!(transaction.message.account_keys.contains(&old_symbol_index));
+        assert_eq!(client.rpc().sent_transactions().len(), 3);
+    }
+
+    #[test]
+    fn test_transactions_trim_policy() {
+        let program_id = Pubkey::new_unique();
+        let client = RegistryClient::new(MockRpc::new(), program_id);
+        let authority = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let policy = ValidationPolicy {
+            symbol_whitespace: Whitespace::Trim,
+            ..ValidationPolicy::default()
+        };
+        add_registry_config(client.rpc(), &program_id, policy);
+
+        // the program claims the index of the trimmed symbol
+        let transaction =
+            client.register_mint_transaction(&mint, String::from(" SYM "), String::from("name"), &authority).unwrap();
+        let symbol_index = find_symbol_index_address(&program_id, &ValidationPolicy::default(), "SYM").0;
+        assert!(transaction.message.account_keys.contains(&symbol_index));
+    }
+}
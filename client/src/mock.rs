@@ -0,0 +1,133 @@
+//! In-memory RPC for tests
+
+use crate::{
+    error::ClientError,
+    rpc::{AccountFilter, RegistryRpc},
+};
+use solana_sdk::{account::Account, hash::Hash, pubkey::Pubkey, signature::Signature, transaction::Transaction};
+use std::{cell::RefCell, collections::BTreeMap};
+
+/// [RegistryRpc](../rpc/trait.RegistryRpc.html) answering from accounts held
+/// in memory, so that code using the client can be tested offline.
+///
+/// Sent transactions must use the blockhash of the mock and carry valid
+/// signatures; they are recorded, not executed.
+#[derive(Debug)]
+pub struct MockRpc {
+    accounts: RefCell<BTreeMap<Pubkey, Account>>,
+    sent: RefCell<Vec<Transaction>>,
+    blockhash: Hash,
+}
+impl Default for MockRpc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl MockRpc {
+    /// Creates a mock without accounts.
+    pub fn new() -> Self {
+        Self {
+            accounts: RefCell::new(BTreeMap::new()),
+            sent: RefCell::new(Vec::new()),
+            blockhash: Hash::new_unique(),
+        }
+    }
+
+    /// Stores an account, replacing any account at the same address.
+    pub fn set_account(&self, pubkey: Pubkey, account: Account) {
+        self.accounts.borrow_mut().insert(pubkey, account);
+    }
+
+    /// Removes an account.
+    pub fn remove_account(&self, pubkey: &Pubkey) -> Option<Account> {
+        self.accounts.borrow_mut().remove(pubkey)
+    }
+
+    /// Transactions sent so far, oldest first.
+    pub fn sent_transactions(&self) -> Vec<Transaction> {
+        self.sent.borrow().clone()
+    }
+}
+impl RegistryRpc for MockRpc {
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, ClientError> {
+        Ok(self.accounts.borrow().get(pubkey).cloned())
+    }
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Result<Vec<(Pubkey, Account)>, ClientError> {
+        Ok(self
+            .accounts
+            .borrow()
+            .iter()
+            .filter(|(_, account)| account.owner == *program_id)
+            .filter(|(_, account)| filters.iter().all(|filter| filter.matches(&account.data)))
+            .map(|(pubkey, account)| (*pubkey, account.clone()))
+            .collect())
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash, ClientError> {
+        Ok(self.blockhash)
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature, ClientError> {
+        if transaction.message.recent_blockhash != self.blockhash {
+            return Err(ClientError::RpcFailed(String::from("Blockhash not found")));
+        }
+        transaction
+            .verify()
+            .map_err(|e| ClientError::RpcFailed(e.to_string()))?;
+        self.sent.borrow_mut().push(transaction.clone());
+        Ok(transaction.signatures[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        instruction::Instruction,
+        signature::{Keypair, Signer},
+    };
+
+    #[test]
+    fn test_mock_rpc() {
+        let rpc = MockRpc::new();
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let other_key = Pubkey::new_unique();
+        rpc.set_account(key, Account::new(1, 2, &program_id));
+        rpc.set_account(other_key, Account::new(1, 3, &Pubkey::new_unique()));
+
+        assert_eq!(rpc.get_account(&key).unwrap(), Some(Account::new(1, 2, &program_id)));
+        assert_eq!(rpc.get_account(&Pubkey::new_unique()).unwrap(), None);
+        assert_eq!(
+            rpc.get_program_accounts(&program_id, &[]).unwrap(),
+            vec![(key, Account::new(1, 2, &program_id))]
+        );
+        assert_eq!(rpc.get_program_accounts(&program_id, &[AccountFilter::DataSize(3)]).unwrap(), vec![]);
+        assert_eq!(rpc.remove_account(&key), Some(Account::new(1, 2, &program_id)));
+        assert_eq!(rpc.get_account(&key).unwrap(), None);
+
+        // only signed transactions with the current blockhash go through
+        let payer = Keypair::new();
+        let instruction = || Instruction::new_with_bytes(program_id, &[], vec![]);
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction()],
+            Some(&payer.pubkey()),
+            &[&payer],
+            rpc.get_latest_blockhash().unwrap(),
+        );
+        assert_eq!(rpc.send_transaction(&transaction), Ok(transaction.signatures[0]));
+        assert_eq!(rpc.sent_transactions(), vec![transaction.clone()]);
+
+        let stale = Transaction::new_signed_with_payer(&[instruction()], Some(&payer.pubkey()), &[&payer], Hash::new_unique());
+        assert!(rpc.send_transaction(&stale).is_err());
+        let mut unsigned = transaction;
+        unsigned.signatures[0] = Signature::default();
+        assert!(rpc.send_transaction(&unsigned).is_err());
+        assert_eq!(rpc.sent_transactions().len(), 1);
+    }
+}
@@ -0,0 +1,122 @@
+//! RPC access used by the client
+
+use crate::error::ClientError;
+use solana_sdk::{account::Account, hash::Hash, pubkey::Pubkey, signature::Signature, transaction::Transaction};
+
+/// Filter applied by getProgramAccounts to the account data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccountFilter {
+    /// data is exactly this long
+    DataSize(u64),
+    /// data holds bytes at offset
+    Memcmp {
+        /// offset of the bytes in the data
+        offset: usize,
+        /// bytes to compare
+        bytes: Vec<u8>,
+    },
+}
+impl AccountFilter {
+    /// Tells whether account data passes the filter, as the RPC node decides.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        match self {
+            Self::DataSize(len) => data.len() as u64 == *len,
+            Self::Memcmp { offset, bytes } => offset
+                .checked_add(bytes.len())
+                .and_then(|end| data.get(*offset..end))
+                == Some(&bytes[..]),
+        }
+    }
+}
+
+/// RPC methods the [RegistryClient](../struct.RegistryClient.html) needs.
+///
+/// Implemented by [MockRpc](../mock/struct.MockRpc.html) for tests and, with
+/// the `rpc-client` feature, by the blocking RpcClient of the Solana SDK.
+pub trait RegistryRpc {
+    /// getAccountInfo, None when the account does not exist
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, ClientError>;
+
+    /// getProgramAccounts, keeping the accounts passing every filter
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Result<Vec<(Pubkey, Account)>, ClientError>;
+
+    /// getLatestBlockhash
+    fn get_latest_blockhash(&self) -> Result<Hash, ClientError>;
+
+    /// sendTransaction, returning once the transaction is confirmed
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature, ClientError>;
+}
+
+#[cfg(feature = "rpc-client")]
+impl RegistryRpc for solana_rpc_client::rpc_client::RpcClient {
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, ClientError> {
+        self.get_account_with_commitment(pubkey, self.commitment())
+            .map(|response| response.value)
+            .map_err(|e| ClientError::RpcFailed(e.to_string()))
+    }
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Result<Vec<(Pubkey, Account)>, ClientError> {
+        use solana_account_decoder::UiAccountEncoding;
+        use solana_rpc_client_api::{
+            config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+            filter::{Memcmp, RpcFilterType},
+        };
+
+        let filters = filters
+            .iter()
+            .map(|filter| match filter {
+                AccountFilter::DataSize(len) => RpcFilterType::DataSize(*len),
+                AccountFilter::Memcmp { offset, bytes } => {
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(*offset, bytes.clone()))
+                }
+            })
+            .collect();
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        self.get_program_accounts_with_config(program_id, config)
+            .map_err(|e| ClientError::RpcFailed(e.to_string()))
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash, ClientError> {
+        self.get_latest_blockhash()
+            .map_err(|e| ClientError::RpcFailed(e.to_string()))
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature, ClientError> {
+        self.send_and_confirm_transaction(transaction)
+            .map_err(|e| ClientError::RpcFailed(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_filter() {
+        let data = [4u8, 1, 2, 3];
+        assert!(AccountFilter::DataSize(4).matches(&data));
+        assert!(!AccountFilter::DataSize(5).matches(&data));
+        assert!(AccountFilter::Memcmp { offset: 0, bytes: vec![4] }.matches(&data));
+        assert!(AccountFilter::Memcmp { offset: 2, bytes: vec![2, 3] }.matches(&data));
+        assert!(AccountFilter::Memcmp { offset: 4, bytes: vec![] }.matches(&data));
+        assert!(!AccountFilter::Memcmp { offset: 1, bytes: vec![2] }.matches(&data));
+        assert!(!AccountFilter::Memcmp { offset: 3, bytes: vec![3, 0] }.matches(&data));
+        assert!(!AccountFilter::Memcmp { offset: usize::MAX, bytes: vec![0] }.matches(&data));
+    }
+}
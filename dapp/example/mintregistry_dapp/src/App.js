@@ -20,10 +20,7 @@ class Content extends React.Component {
                   symbol:'SRM',
                   name:"SRM",
                   extAccount:'',
-                  decimals:6,
-                  supply:100000000,
-                  mintAuthority:"HSfwVfB7RUF1SKCd4yrz8KZp7TU262Y5BeZZN1tdCTVk",
-                  freezeAuthority:"HSfwVfB7RUF1SKCd4yrz8KZp7TU262Y5BeZZN1tdCTVk",
+                  registeredSymbol:'',
                 };
     this.onImport = this.onImport.bind(this);
     this.onRegister = this.onRegister.bind(this);
@@ -34,10 +31,6 @@ class Content extends React.Component {
     this.onModify = this.onModify.bind(this);
     this.onClose = this.onClose.bind(this);
     this.onQueryBySymbol = this.onQueryBySymbol.bind(this);
-    this.onMintAuthority = this.onMintAuthority.bind(this);
-    this.onFreezeAuthority = this.onFreezeAuthority.bind(this);
-    this.onSupply = this.onSupply.bind(this);
-    this.onDecimals = this.onDecimals.bind(this);
     this.onMnemonic = this.onMnemonic.bind(this);
 
     //let url =  'http://api.mainnet-beta.solana.com';
@@ -60,10 +53,6 @@ class Content extends React.Component {
         </React.Fragment>
         <Divider />
         <React.Fragment>
-        <TextField multiline label="mint_authority" onChange={this.onMintAuthority}/>
-        <TextField multiline label="freeze_authority" onChange={this.onFreezeAuthority}/>
-        <TextField multiline label="supply" onChange={this.onSupply}/>
-        <TextField multiline label="decimals" onChange={this.onDecimals}/>
           <TextField multiline label="mint" onChange={this.onMint}/>
           <TextField multiline label="symbol" onChange={this.onSymbol}/>
           <TextField multiline label="name" onChange={this.onName}/>
@@ -115,12 +104,13 @@ class Content extends React.Component {
     MintRegistry.ModifyMint(
       this.connection,
       this.account,
-      new PublicKey(this.state.extAccount),
       new PublicKey(this.state.mint),
+      this.state.registeredSymbol,
       this.state.symbol,
       this.state.name,
       this.programID,
     ).then(()=>{
+      this.setState({registeredSymbol:this.state.symbol})
       console.log("done modify");
     }).catch((e)=>{
       console.log("modify error:", e);
@@ -131,8 +121,8 @@ class Content extends React.Component {
     MintRegistry.CloseMint(
       this.connection,
       this.account,
-      new PublicKey(this.state.extAccount),
       new PublicKey(this.state.mint),
+      this.state.registeredSymbol,
       this.programID,
     ).then(()=>{
       console.log("done close");
@@ -148,6 +138,9 @@ class Content extends React.Component {
       this.programID,
     ).then((exts)=>{
       console.log(exts);
+      if (exts) {
+        this.setState({extAccount:exts[0].extension, registeredSymbol:exts[0].symbol})
+      }
     });
   }
 
@@ -163,39 +156,18 @@ class Content extends React.Component {
   }
 
   onRegister() {
-    console.log("this.state.mintAuthority:",this.state.mintAuthority)
     MintRegistry.RegisterMint(
       this.connection,
       this.account,
-      new PublicKey(this.state.mintAuthority),
-      new PublicKey(this.state.freezeAuthority),
-      this.state.supply,
-      this.state.decimals,
       new PublicKey(this.state.mint),
       this.state.symbol,
       this.state.name,
       this.programID,
     ).then((ext)=>{
-      this.setState({extAccount:ext.extension})
+      this.setState({extAccount:ext.extension, registeredSymbol:ext.symbol})
     });
   }
 
-  onSupply(e) {
-    this.setState({supply:parseInt(e.target.value)}); 
-  }
-
-  onMintAuthority(e) {
-    this.setState({mintAuthority:e.target.value}); 
-  }
-
-  onFreezeAuthority(e) {
-    this.setState({freezeAuthority:e.target.value}); 
-  }
-
-  onDecimals(e) {
-    this.setState({decimals:parseInt(e.target.value)}); 
-  }
-
   onMint(e) {
     this.setState({mint:e.target.value}); 
   }
@@ -1,4 +1,4 @@
-// @flow
+  // @flow
 
 import * as BufferLayout from 'buffer-layout';
 
@@ -15,21 +15,57 @@ export function publicKey(property) {
  export function uint64(property){
     return BufferLayout.blob(8, property);
 };
-  
 
 /**
- * Layout for MintExtension
+ * Tag in the first byte of accounts written by the program
  */
+export const AccountType = {
+    MintExtensionV1: 1,
+    MintExtensionV2: 2,
+    SymbolIndex: 3,
+    RegistryConfig: 4,
+    MintExtensionV3: 5,
+};
+
+/**
+ * Tags of the MintExtension layouts encoded with Borsh, whose fields start
+ * like MintExtensionLayout
+ */
+export const BorshMintExtensionTypes = [
+    AccountType.MintExtensionV2,
+    AccountType.MintExtensionV3,
+];
+
+function mintExtensionFields(symbolNameLen) {
+    return [
+        publicKey('mint_authority'),
+        publicKey('freeze_authority'),
+        uint64('supply'),
+        BufferLayout.u8('decimals'),
+        BufferLayout.u8('isInitialized'),
+        publicKey('mint'),
+        BufferLayout.u8('symbolLength'),
+        BufferLayout.blob(symbolNameLen,'symbol'),
+        BufferLayout.u8('nameLength'),
+        BufferLayout.blob(symbolNameLen,'name'),
+    ];
+}
+
+/**
+ * Layout for MintExtension, after the tag of the Borsh encoded layouts.
+ * Later layouts append fields, which this layout leaves out.
+ */
+export const MintExtensionLayout = BufferLayout.struct(mintExtensionFields(32));
 
-export const MintExtensionLayout = BufferLayout.struct([
-    publicKey('mint_authority'),
-    publicKey('freeze_authority'),
-    BufferLayout.u8('isInitialized'),
-    uint64(8,'supply'), 
-    BufferLayout.u8('decimals'),
+/**
+ * Layout for MintExtension written before accounts were tagged (V0), and
+ * after the tag of the V1 layout
+ */
+export const LegacyMintExtensionLayout = BufferLayout.struct(mintExtensionFields(16));
+
+/**
+ * Layout for SymbolIndex, after its tag
+ */
+export const SymbolIndexLayout = BufferLayout.struct([
     publicKey('mint'),
-    BufferLayout.u8('symbolLength'),
-    BufferLayout.blob(16,'symbol'), 
-    BufferLayout.u8('nameLength'),
-    BufferLayout.blob(16,'name'), 
 ]);
@@ -2,17 +2,16 @@
  * @flow
  */
 
-import * as BufferLayout from 'buffer-layout';
 import * as Layout from './layout';
 import { Connection,
     Transaction,
     PublicKey,
     SystemProgram,
+    SYSVAR_RENT_PUBKEY,
     TransactionInstruction,
-    sendAndConfirmTransaction,
-    Account} from "@solana/web3.js"
+    sendAndConfirmTransaction} from "@solana/web3.js"
 import bs58 from 'bs58';
-import {u64} from '@solana/spl-token'
+import {sha256} from 'crypto-hash';
 import {OldMints} from './mintext_snapshot'
 
 /**
@@ -79,84 +78,157 @@ import {OldMints} from './mintext_snapshot'
     }
  }
 
+/**
+ * Decode the data of a MintExtension account in any layout,
+ * null when the data is not an initialized MintExtension
+ *
+ * @param extension address of the account
+ * @param b account data
+ */
+export function decodeMintExtension(extension, b) {
+    let layout;
+    let offset;
+    if (b.length === Layout.LegacyMintExtensionLayout.span) {
+        // untagged, written before accounts were versioned
+        layout = Layout.LegacyMintExtensionLayout;
+        offset = 0;
+    } else if (b[0] === Layout.AccountType.MintExtensionV1) {
+        layout = Layout.LegacyMintExtensionLayout;
+        offset = 1;
+    } else if (Layout.BorshMintExtensionTypes.includes(b[0])) {
+        layout = Layout.MintExtensionLayout;
+        offset = 1;
+    } else {
+        return null;
+    }
+    if (b.length < offset + layout.span) {
+        return null;
+    }
+    const ext = layout.decode(b, offset);
+    if (ext.isInitialized !== 1) {
+        return null;
+    }
+    return new MintExtension(
+        extension,
+        new PublicKey(ext.mint_authority).toBase58(),
+        new PublicKey(ext.freeze_authority).toBase58(),
+        intFromBytes(ext.supply),
+        ext.decimals,
+        new PublicKey(ext.mint).toBase58(),
+        ext.symbol.slice(0, ext.symbolLength).toString(),
+        ext.name.slice(0, ext.nameLength).toString(),
+    );
+}
+
+// symbol or name prefixed with its length in bytes
+function encodeString(value) {
+    const buf = Buffer.from(value, 'utf8');
+    if (buf.length > 255) {
+        throw new Error('string longer than 255 bytes: ' + value);
+    }
+    return Buffer.concat([Buffer.from([buf.length]), buf]);
+}
+
 /**
  * MintRegistry
+ *
+ * Every instruction takes the RegistryConfig first. MintExtension and
+ * SymbolIndex accounts live at addresses derived from the mint and the
+ * symbol, and are created and closed by the program.
  */
 export class MintRegistry {
 
     /**
-     * Get the minimum balance for the mint extension to be rent exempt
+     * Derive the address of the RegistryConfig
      *
-     * @return Number of lamports required
+     * @param programID MintRegistry program account
      */
-    static async getMinBalanceRentForExemptMintExtension(
-        connection
-    ) {
-        return await connection.getMinimumBalanceForRentExemption(Layout.MintExtensionLayout.span);
+    static async findRegistryConfigAddress(programID) {
+        const [address] = await PublicKey.findProgramAddress(
+            [Buffer.from('registry_config')],
+            programID,
+        );
+        return address;
+    }
+
+    /**
+     * Derive the address of the MintExtension of a mint
+     *
+     * @param mint mint account
+     * @param programID MintRegistry program account
+     */
+    static async findMintExtensionAddress(mint, programID) {
+        const [address] = await PublicKey.findProgramAddress(
+            [Buffer.from('mint_extension'), mint.toBuffer()],
+            programID,
+        );
+        return address;
+    }
+
+    /**
+     * Normalise a symbol the way the program does before deriving its
+     * SymbolIndex, see validation::normalize_symbol
+     *
+     * @param symbol symbol of mint
+     * @param trimSymbol whether the registry policy trims symbols
+     */
+    static normalizeSymbol(symbol, trimSymbol = false) {
+        return (trimSymbol ? symbol.trim() : symbol).toUpperCase();
+    }
+
+    /**
+     * Derive the address of the SymbolIndex of a symbol, the same for
+     * symbols differing only in case
+     *
+     * @param symbol symbol of mint
+     * @param programID MintRegistry program account
+     * @param trimSymbol whether the registry policy trims symbols
+     */
+    static async findSymbolIndexAddress(symbol, programID, trimSymbol = false) {
+        const normalized = this.normalizeSymbol(symbol, trimSymbol);
+        const symbolHash = await sha256(Buffer.from(normalized, 'utf8'), {outputFormat: 'buffer'});
+        const [address] = await PublicKey.findProgramAddress(
+            [Buffer.from('symbol_index'), Buffer.from(symbolHash)],
+            programID,
+        );
+        return address;
     }
 
     /**
      * Construct an RegisterMint instruction
      *
+     * The supply, decimals and authorities are read from the mint account.
+     *
      * @param mint mint account
      * @param symbol symbol of mint
      * @param name name of mint
-     * @param payer payer account 
-     * @param extAccount mint extension account
-     * @param programID SPL Token program account
+     * @param payer mint authority, paying for the new accounts
+     * @param programID MintRegistry program account
+     * @param trimSymbol whether the registry policy trims symbols
      */
-    static createRegisterMintInstruction(
-        mint_authority,
-        freeze_authority,
-        supply,
-        decimals,
+    static async createRegisterMintInstruction(
         mint,
         symbol,
         name,
         payer,
-        extAccount,
         programID,
+        trimSymbol = false,
     ) {
-        console.log("mint :", mint.toBase58());
-        console.log("payer :", payer.toBase58());
-        console.log("extAccount :", extAccount.toBase58());
-        console.log("programID :", programID.toBase58());
-        const dataLayout = BufferLayout.struct([
-            BufferLayout.u8("i"),
-            BufferLayout.blob(32,"mint_authority"),
-            BufferLayout.blob(32,"freeze_authority"),
-            BufferLayout.blob(8,"supply"),
-            BufferLayout.u8("decimals"),
-            BufferLayout.blob(32,"mint"),
-            BufferLayout.u8('symbol_len'),
-            BufferLayout.blob(symbol.length,"symbol"),
-            BufferLayout.u8('name_len'),
-            BufferLayout.blob(name.length,"name"),
+        const data = Buffer.concat([
+            Buffer.from([1]), // register mint instruction
+            mint.toBuffer(),
+            encodeString(symbol),
+            encodeString(name),
         ]);
-      
-        const data = Buffer.alloc(dataLayout.span);
-        const supplyBuffer = Buffer.alloc(8);
-        supplyBuffer.set(new u64(supply).toBuffer(),0);
-        dataLayout.encode(
-            {
-              i:1, // register mint instruction
-              mint_authority:mint_authority.toBuffer(),
-              freeze_authority:freeze_authority.toBuffer(),
-              supply:supplyBuffer,
-              decimals: decimals,
-              mint: mint.toBuffer(), 
-              symbol_len:symbol.length,
-              symbol:Buffer.from(symbol, 'utf8'),
-              name_len:name.length,
-              name:Buffer.from(name, 'utf8'),
-            },
-            data,
-        );
-      
+
         let keys = [
-            {pubkey: mint, isSigner: false, isWritable: true},
+            {pubkey: await this.findRegistryConfigAddress(programID), isSigner: false, isWritable: true},
+            {pubkey: mint, isSigner: false, isWritable: false},
             {pubkey: payer, isSigner: true, isWritable: true},
-            {pubkey: extAccount, isSigner: true, isWritable: true},
+            {pubkey: await this.findMintExtensionAddress(mint, programID), isSigner: false, isWritable: true},
+            {pubkey: SystemProgram.programId, isSigner: false, isWritable: false},
+            {pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false},
+            {pubkey: await this.findSymbolIndexAddress(symbol, programID, trimSymbol), isSigner: false, isWritable: true},
         ];
 
         const  trxi = new TransactionInstruction({
@@ -171,46 +243,37 @@ export class MintRegistry {
      * Construct an ModifyMint instruction
      *
      * @param mint mint account
-     * @param symbol symbol of mint
-     * @param name name of mint
-     * @param payer payer account 
-     * @param extAccount mint extension account
-     * @param programID SPL Token program account
+     * @param oldSymbol symbol currently registered, whose SymbolIndex is closed
+     * @param symbol new symbol of mint
+     * @param name new name of mint
+     * @param payer update authority, paying for the new SymbolIndex
+     * @param programID MintRegistry program account
+     * @param trimSymbol whether the registry policy trims symbols
      */
-    static createModifyMintInstruction(
+    static async createModifyMintInstruction(
         mint,
+        oldSymbol,
         symbol,
         name,
         payer,
-        extAccount,
         programID,
+        trimSymbol = false,
     ) {
-        console.log("symbol:", symbol);
-        console.log("name:", name);
-        const dataLayout = BufferLayout.struct([
-            BufferLayout.u8("i"),
-            BufferLayout.u8('symbol_len'),
-            BufferLayout.blob(symbol.length,"symbol"),
-            BufferLayout.u8('name_len'),
-            BufferLayout.blob(name.length,"name"),
+        const data = Buffer.concat([
+            Buffer.from([3]), // modify mint instruction
+            encodeString(symbol),
+            encodeString(name),
         ]);
-      
-        const data = Buffer.alloc(dataLayout.span);
-        dataLayout.encode(
-            {
-                i:3, // modify mint instruction
-                symbol_len:symbol.length,
-                symbol:Buffer.from(symbol, 'utf8'),
-                name_len:name.length,
-                name:Buffer.from(name, 'utf8'),
-            },
-            data,
-        );
-      
+
         let keys = [
-            {pubkey: mint, isSigner: false, isWritable: true},
+            {pubkey: await this.findRegistryConfigAddress(programID), isSigner: false, isWritable: false},
+            {pubkey: mint, isSigner: false, isWritable: false},
             {pubkey: payer, isSigner: true, isWritable: true},
-            {pubkey: extAccount, isSigner: false, isWritable: true},
+            {pubkey: await this.findMintExtensionAddress(mint, programID), isSigner: false, isWritable: true},
+            {pubkey: SystemProgram.programId, isSigner: false, isWritable: false},
+            {pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false},
+            {pubkey: await this.findSymbolIndexAddress(oldSymbol, programID, trimSymbol), isSigner: false, isWritable: true},
+            {pubkey: await this.findSymbolIndexAddress(symbol, programID, trimSymbol), isSigner: false, isWritable: true},
         ];
 
         const  trxi = new TransactionInstruction({
@@ -225,33 +288,25 @@ export class MintRegistry {
      * Construct an CloseMint instruction
      *
      * @param mint mint account
-     * @param payer payer account 
-     * @param extAccount mint extension account
-     * @param programID SPL Token program account
+     * @param symbol symbol currently registered, whose SymbolIndex is closed
+     * @param payer update authority, getting back the rent
+     * @param programID MintRegistry program account
      */
-    static createCloseMintInstruction(
+    static async createCloseMintInstruction(
         mint,
+        symbol,
         payer,
-        extAccount,
         programID,
     ) {
+        const data = Buffer.from([2]); // close mint instruction
 
-        const dataLayout = BufferLayout.struct([
-            BufferLayout.u8("i"),
-        ]);
-      
-        const data = Buffer.alloc(dataLayout.span);
-            dataLayout.encode(
-            {
-                i:2, // close mint instruction
-            },
-            data,
-        );
-      
         let keys = [
-            {pubkey: extAccount, isSigner: false, isWritable: true},
-            {pubkey: payer, isSigner: true, isWritable: true},
-            {pubkey: mint, isSigner: false, isWritable: true},
+            {pubkey: await this.findRegistryConfigAddress(programID), isSigner: false, isWritable: false},
+            {pubkey: mint, isSigner: false, isWritable: false},
+            {pubkey: payer, isSigner: true, isWritable: false},
+            {pubkey: await this.findMintExtensionAddress(mint, programID), isSigner: false, isWritable: true},
+            {pubkey: await this.findSymbolIndexAddress(symbol, programID), isSigner: false, isWritable: true},
+            {pubkey: payer, isSigner: false, isWritable: true},
         ];
 
         const  trxi = new TransactionInstruction({
@@ -267,8 +322,8 @@ export class MintRegistry {
      * Regist an extension for Mint.
      *
      * @param connection The connection to use
-     * @param payer Fee payer and mint maker for transaction
-     * @param mint the mint 
+     * @param payer Fee payer and mint authority for transaction
+     * @param mint the mint
      * @param symbol symbol for mint
      * @param name  name for mint
      * @param programID RegisterMint's address
@@ -276,66 +331,39 @@ export class MintRegistry {
     static async RegisterMint(
         connection,
         payer,
-        mint_authority,
-        freeze_authority,
-        supply,
-        decimals,
         mint,
         symbol,
         name,
         programID,
     ){
-        const extAccount = new Account();
-        const balanceNeeded = await this.getMinBalanceRentForExemptMintExtension(connection);
-
-        const trxi0 =  SystemProgram.createAccount({
-            fromPubkey: payer.publicKey,
-            newAccountPubkey: extAccount.publicKey,
-            lamports: balanceNeeded,
-            space: Layout.MintExtensionLayout.span,
-            programId: programID,
-        });
-
-        const trxi1 = this.createRegisterMintInstruction(
-            mint_authority,
-            freeze_authority,
-            supply,
-            decimals,
+        const trxi0 = await this.createRegisterMintInstruction(
             mint,
             symbol,
             name,
             payer.publicKey,
-            extAccount.publicKey,
             programID,
         );
-        console.log("mint_authority:", mint_authority.toBase58())
-        console.log("supply:", supply)
 
         const transaction = new Transaction();
         transaction.add(trxi0);
-        transaction.add(trxi1);
 
-        let signers= [payer, extAccount];
+        let signers= [payer];
         await sendAndConfirmTransaction(connection, transaction, signers, {
             skipPreflight: false,
             commitment: 'recent',
             preflightCommitment: 'recent',
         });
-        return new MintExtension(
-            extAccount.publicKey.toBase58(),
-            mint,
-            symbol,
-            name,
-        );
+        const exts = await this.GetMintExtension(connection, mint, programID);
+        return exts && exts[0];
     }
 
     /**
      * Modify an extension for Mint.
      *
      * @param connection The connection to use
-     * @param payer Fee payer and mint maker for transaction
-     * @param extAccount mint extension account 
-     * @param mint the mint 
+     * @param payer Fee payer and update authority for transaction
+     * @param mint the mint
+     * @param oldSymbol symbol currently registered for mint
      * @param symbol symbol for mint
      * @param name  name for mint
      * @param programID RegisterMint's address
@@ -343,18 +371,18 @@ export class MintRegistry {
     static async ModifyMint(
         connection,
         payer,
-        extAccount,
         mint,
+        oldSymbol,
         symbol,
         name,
         programID,
     ){
-        const trxi0 = this.createModifyMintInstruction(
+        const trxi0 = await this.createModifyMintInstruction(
             mint,
+            oldSymbol,
             symbol,
             name,
             payer.publicKey,
-            extAccount,
             programID,
         );
 
@@ -373,22 +401,22 @@ export class MintRegistry {
      * Close an extension for Mint.
      *
      * @param connection The connection to use
-     * @param payer Fee payer and mint maker for transaction
-     * @param extAccount mint extension account 
-     * @param mint the mint 
+     * @param payer Fee payer and update authority for transaction
+     * @param mint the mint
+     * @param symbol symbol currently registered for mint
      * @param programID RegisterMint's address
      */
     static async CloseMint(
         connection,
         payer,
-        extAccount,
         mint,
+        symbol,
         programID,
     ){
-        const trxi0 = this.createCloseMintInstruction(
+        const trxi0 = await this.createCloseMintInstruction(
             mint,
+            symbol,
             payer.publicKey,
-            extAccount,
             programID,
         );
 
@@ -400,14 +428,14 @@ export class MintRegistry {
             skipPreflight: false,
             commitment: 'recent',
             preflightCommitment: 'recent',
-        });        
+        });
     }
 
     /**
      * Get extension for Mint.
      *
      * @param connection The connection to use
-     * @param mint the mint 
+     * @param mint the mint
      * @param programID RegisterMint's address
      */
     static async GetMintExtension(
@@ -415,46 +443,23 @@ export class MintRegistry {
         mint,
         programID,
     ) {
-       
-        let resp = await connection._rpcRequest('getProgramAccounts', [
-            programID.toBase58(),
-            {
-              encoding:'jsonParsed',
-              commitment: 'recent',
-                filters:[{"dataSize": 140},{"memcmp": {"offset": 1+73, "bytes": mint.toBase58()}}]
-            }
-        ])
-        if (resp.result && resp.result.length > 0 ) {
-            let exts = [];
-            resp.result.forEach( result =>{
-                const account = result.account;
-                const data = account.data[0];
-                const b = Buffer.from(data, 'base64');
-                const ma = new PublicKey(b.slice(0,32));
-                const fa = new PublicKey(b.slice(32,64));
-                const supply = intFromBytes(b.slice(64,72));
-                const decimals = b.slice(72,73)[0];
-
-                const p = new PublicKey(b.slice(74,106));
-                let l = b.slice(106,107)[0];
-                const s = b.slice(107,107+l).toString();
-                l = b.slice(123,124)[0];
-                const n = b.slice(124,124+l).toString();
-
-                const ext = new MintExtension(result.pubkey, ma.toBase58(), fa.toBase58(), supply, decimals, p.toBase58(),s,n);
-                exts.push(ext);
-            });
-            return exts;
-        } else {
+        const extension = await this.findMintExtensionAddress(mint, programID);
+        const account = await connection.getAccountInfo(extension, 'recent');
+        if (!account || !account.owner.equals(programID)) {
+            return null;
+        }
+        const ext = decodeMintExtension(extension.toBase58(), account.data);
+        if (!ext || ext.mint !== mint.toBase58()) {
             return null;
         }
+        return [ext];
     }
 
     /**
-     * Get extension for Mint with SYMBOL.
+     * Get extension for Mint with SYMBOL, whatever its case.
      *
      * @param connection The connection to use
-     * @param symbol symbol for the mint 
+     * @param symbol symbol for the mint
      * @param programID RegisterMint's address
      */
     static async GetMintExtensionBySymbol(
@@ -462,49 +467,25 @@ export class MintRegistry {
         symbol,
         programID,
     ) {
-        const symbolBuf = Buffer.from(symbol)
-        const filter = bs58.encode(symbolBuf)
-        let resp = await connection._rpcRequest('getProgramAccounts', [
-            programID.toBase58(),
-            {
-              encoding:'jsonParsed',
-              commitment: 'recent',
-                filters:[{"dataSize": 140},{"memcmp": {"offset": 34+73, "bytes": filter}}]
-            }
-        ])
-        if (resp.result && resp.result.length > 0 ) {
-            let exts = [];
-            resp.result.forEach( result =>{
-                const account = result.account;
-                const data = account.data[0];
-                const b = Buffer.from(data, 'base64');
-
-                const ma = new PublicKey(b.slice(0,32));
-                const fa = new PublicKey(b.slice(32,64));
-                const supply = intFromBytes(b.slice(64,72));
-                const decimals = b.slice(72,73)[0];
-
-                const p = new PublicKey(b.slice(74,106));
-                let l = b.slice(106,107)[0];
-                const s = b.slice(107,107+l).toString();
-                l = b.slice(123,124)[0];
-                const n = b.slice(124,124+l).toString();
-
-                const ext = new MintExtension(result.pubkey, ma.toBase58(), fa.toBase58(), supply, decimals, p.toBase58(),s,n);
-                exts.push(ext);
-            });
-            return exts;
-        } else {
+        const index = await this.findSymbolIndexAddress(symbol, programID);
+        const account = await connection.getAccountInfo(index, 'recent');
+        if (!account || !account.owner.equals(programID)
+            || account.data[0] !== Layout.AccountType.SymbolIndex) {
             return null;
         }
+        const {mint} = Layout.SymbolIndexLayout.decode(account.data, 1);
+        return this.GetMintExtension(connection, new PublicKey(mint), programID);
     }
 
 
     /**
      * Get extension for Mint with mint authority.
      *
+     * The mint authority is the one cached at registration or by the last
+     * SyncMint. Every layout is scanned with its own request.
+     *
      * @param connection The connection to use
-     * @param mintAuthority mintAuthoritysymbol for the mint 
+     * @param mintAuthority mintAuthoritysymbol for the mint
      * @param programID RegisterMint's address
      */
      static async GetMintExtensionByMintAuthority(
@@ -512,47 +493,43 @@ export class MintRegistry {
         mintAuthority,
         programID,
     ) {
-        let resp = await connection._rpcRequest('getProgramAccounts', [
-            programID.toBase58(),
-            {
-              encoding:'jsonParsed',
-              commitment: 'recent',
-                filters:[{"dataSize": 140},{"memcmp": {"offset": 0, "bytes": mintAuthority.toBase58()}}]
+        const authority = mintAuthority.toBase58();
+        const scans = [
+            [{"dataSize": Layout.LegacyMintExtensionLayout.span}, {"memcmp": {"offset": 0, "bytes": authority}}],
+            ...[Layout.AccountType.MintExtensionV1, ...Layout.BorshMintExtensionTypes].map(tag => [
+                {"memcmp": {"offset": 0, "bytes": bs58.encode(Buffer.from([tag]))}},
+                {"memcmp": {"offset": 1, "bytes": authority}},
+            ]),
+        ];
+        let exts = [];
+        for (const filters of scans) {
+            let resp = await connection._rpcRequest('getProgramAccounts', [
+                programID.toBase58(),
+                {
+                  encoding:'base64',
+                  commitment: 'recent',
+                  filters,
+                }
+            ])
+            if (resp.result) {
+                resp.result.forEach( result =>{
+                    const b = Buffer.from(result.account.data[0], 'base64');
+                    const ext = decodeMintExtension(result.pubkey, b);
+                    // untagged accounts may start with the byte of a tag
+                    if (ext && ext.mint_authority === authority && !exts.some(e => e.extension === ext.extension)) {
+                        exts.push(ext);
+                    }
+                });
             }
-        ])
-        if (resp.result && resp.result.length > 0 ) {
-            let exts = [];
-            resp.result.forEach( result =>{
-                const account = result.account;
-                const data = account.data[0];
-                const b = Buffer.from(data, 'base64');
-
-                const ma = new PublicKey(b.slice(0,32));
-                const fa = new PublicKey(b.slice(32,64));
-                const supply = intFromBytes(b.slice(64,72));
-                const decimals = b.slice(72,73)[0];
-
-                const p = new PublicKey(b.slice(74,106));
-                let l = b.slice(106,107)[0];
-                const s = b.slice(107,107+l).toString();
-                l = b.slice(123,124)[0];
-                const n = b.slice(124,124+l).toString();
-
-                const ext = new MintExtension(result.pubkey, ma.toBase58(), fa.toBase58(), supply, decimals, p.toBase58(),s,n);
-                exts.push(ext);
-            });
+        }
 
-            OldMints.forEach(m =>{
-                if (m.mintAuthority == mintAuthority.toBase58()) {
-                    const ext = new MintExtension("",m.mintAuthority, m.freezeAuthority,0,m.decimals,m.mint,m.symbol, m.name);
-                    exts.push(ext);
-                }
-            })
+        OldMints.forEach(m =>{
+            if (m.mintAuthority == authority) {
+                const ext = new MintExtension("",m.mintAuthority, m.freezeAuthority,0,m.decimals,m.mint,m.symbol, m.name);
+                exts.push(ext);
+            }
+        })
 
-            return exts;
-        } else {
-            return null;
-        }
+        return exts.length > 0 ? exts : null;
     }
 }
-  
\ No newline at end of file
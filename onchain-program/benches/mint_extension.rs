@@ -0,0 +1,98 @@
+//! Cost of the MintExtension accesses made by ModifyMint, the baseline path
+//! against reading and writing fields in place with MintExtensionView.
+//!
+//! The baseline is the code ModifyMint ran before the view existed: the
+//! 140-byte extension copied out with `unpack_unchecked`, symbol and name
+//! copied byte by byte, and the whole extension copied back with `pack`.
+//! Both sides work on the same V0 account, the only layout the baseline
+//! knew.
+//!
+//! This measures host wall time, not compute units. Compute units are only
+//! counted by the SBF VM, and the host build differs from the SBF one in
+//! instruction set, inlining and memory access costs, so these numbers only
+//! tell which approach copies less; they are no CU figure. To compare compute
+//! units, deploy the program built before and after a change to a local
+//! validator and read the `consumed N of M compute units` line the runtime
+//! logs for each instruction.
+//!
+//! Run with `cargo bench --bench mint_extension`.
+
+#[macro_use]
+extern crate bencher;
+
+use bencher::{black_box, Bencher};
+use mint_registry::{
+    solana_program::{program_pack::Pack, pubkey::Pubkey},
+    state::{MintExtension, MintExtensionLayout, MintExtensionView, MAX_SYMBOL_NAME_LEN},
+};
+
+fn registered_account() -> (Pubkey, Vec<u8>) {
+    let mint = Pubkey::new_unique();
+    let mut symbol = [0; MAX_SYMBOL_NAME_LEN];
+    symbol[..4].copy_from_slice(b"USDC");
+    // a V0 account records no update authority, it is the mint authority
+    let authority = Pubkey::new_unique();
+    let mint_ext = MintExtension {
+        mint_authority: authority,
+        update_authority: authority,
+        is_initialized: true,
+        mint,
+        symbol_len: 4,
+        symbol,
+        ..MintExtension::default()
+    };
+    let mut data = vec![0; MintExtension::account_len(MintExtensionLayout::V0)];
+    mint_ext.pack_account(MintExtensionLayout::V0, &mut data).unwrap();
+    (mint, data)
+}
+
+#[allow(clippy::needless_range_loop)]
+fn modify_baseline(bench: &mut Bencher) {
+    let (mint, mut data) = registered_account();
+    bench.iter(|| {
+        let mut mint_ext = MintExtension::unpack_unchecked(black_box(&data)).unwrap();
+        assert!(mint_ext.is_initialized && mint_ext.mint == mint);
+        black_box(mint_ext.mint_authority);
+        let symbol = "USDT";
+        let name = "Tether USD";
+        mint_ext.symbol_len = symbol.len() as u8;
+        for i in 0..symbol.len() {
+            mint_ext.symbol[i] = symbol.as_bytes()[i];
+        }
+        mint_ext.name_len = name.len() as u8;
+        for i in 0..name.len() {
+            mint_ext.name[i] = name.as_bytes()[i];
+        }
+        MintExtension::pack(mint_ext, &mut data).unwrap();
+    });
+}
+
+fn modify_in_place(bench: &mut Bencher) {
+    let (mint, mut data) = registered_account();
+    bench.iter(|| {
+        let mut mint_ext = MintExtensionView::new(black_box(&mut data[..])).unwrap();
+        assert!(mint_ext.is_initialized() && mint_ext.mint() == mint);
+        black_box(mint_ext.mint_authority());
+        mint_ext.set_symbol("USDT").unwrap();
+        mint_ext.set_name("Tether USD").unwrap();
+    });
+}
+
+fn read_baseline(bench: &mut Bencher) {
+    let (_, data) = registered_account();
+    bench.iter(|| {
+        let mint_ext = MintExtension::unpack_unchecked(black_box(&data)).unwrap();
+        black_box((mint_ext.mint, mint_ext.mint_authority));
+    });
+}
+
+fn read_in_place(bench: &mut Bencher) {
+    let (_, data) = registered_account();
+    bench.iter(|| {
+        let mint_ext = MintExtensionView::new(black_box(&data[..])).unwrap();
+        black_box((mint_ext.mint(), mint_ext.mint_authority()));
+    });
+}
+
+benchmark_group!(benches, modify_baseline, modify_in_place, read_baseline, read_in_place);
+benchmark_main!(benches);
@@ -4,7 +4,6 @@
 use num_derive::FromPrimitive;
 use solana_program::{decode_error::DecodeError, 
     program_error::ProgramError,
-    info,
     msg,
     program_error::PrintProgramError};
 use thiserror::Error;
@@ -18,7 +17,7 @@ pub enum RegistryError {
     InvalidInstruction,
 
     ///  SymbolToLong
-    #[error("Symbol or Name is to long (<16)")]
+    #[error("Symbol or Name exceeds the configured maximum length")]
     SymbolToLong,
 
     /// NoAuthority
@@ -44,6 +43,142 @@ pub enum RegistryError {
     /// TestError 
     #[error("TestError")]
     TestError,
+
+    /// InvalidMintExtensionAddress
+    #[error("Mint Extension address is not derived from the mint")]
+    InvalidMintExtensionAddress,
+
+    /// InvalidMintOwner
+    #[error("Mint account is not owned by the SPL Token program")]
+    InvalidMintOwner,
+
+    /// MintMismatch
+    #[error("Mint Extension does not belong to the mint")]
+    MintMismatch,
+
+    /// InvalidMintExtensionOwner
+    #[error("Mint Extension account is not owned by the registry program")]
+    InvalidMintExtensionOwner,
+
+    /// TruncatedInstruction
+    #[error("Instruction data ended before all fields were read")]
+    TruncatedInstruction,
+
+    /// InvalidStringLength
+    #[error("String length is longer than the remaining instruction data")]
+    InvalidStringLength,
+
+    /// InvalidUtf8
+    #[error("String in instruction is not valid UTF-8")]
+    InvalidUtf8,
+
+    /// TrailingBytes
+    #[error("Instruction data has unexpected trailing bytes")]
+    TrailingBytes,
+
+    /// MetadataTooLong
+    #[error("Metadata field is longer than allowed")]
+    MetadataTooLong,
+
+    /// TooManyTags
+    #[error("Too many tags")]
+    TooManyTags,
+
+    /// InvalidTag
+    #[error("Tag is empty or longer than allowed")]
+    InvalidTag,
+
+    /// InvalidCharacter
+    #[error("Symbol or Name contains a character that is not allowed")]
+    InvalidCharacter,
+
+    /// UnexpectedWhitespace
+    #[error("Symbol or Name has leading, trailing or repeated whitespace")]
+    UnexpectedWhitespace,
+
+    /// ReservedSymbol
+    #[error("Symbol is reserved")]
+    ReservedSymbol,
+
+    /// EmptySymbol
+    #[error("Symbol is empty")]
+    EmptySymbol,
+
+    /// InvalidSymbolIndexAddress
+    #[error("Symbol index account is not derived from the symbol")]
+    InvalidSymbolIndexAddress,
+
+    /// InvalidSymbolIndexOwner
+    #[error("Symbol index account is not owned by the program")]
+    InvalidSymbolIndexOwner,
+
+    /// SymbolTaken
+    #[error("Symbol is already registered to another mint")]
+    SymbolTaken,
+
+    /// InvalidRegistryConfigAddress
+    #[error("Registry config account is not the one derived by the program")]
+    InvalidRegistryConfigAddress,
+
+    /// RegistryNotInitialized
+    #[error("Registry config has not been initialized")]
+    RegistryNotInitialized,
+
+    /// RegistryPaused
+    #[error("Registry is paused")]
+    RegistryPaused,
+
+    /// NotAdmin
+    #[error("Signer is not the registry admin")]
+    NotAdmin,
+
+    /// NotPendingAdmin
+    #[error("Signer is not the pending registry admin")]
+    NotPendingAdmin,
+
+    /// NotUpgradeAuthority
+    #[error("Signer is not the upgrade authority of the program")]
+    NotUpgradeAuthority,
+
+    /// NotCurator
+    #[error("Key is not a registry curator")]
+    NotCurator,
+
+    /// TooManyCurators
+    #[error("Registry has too many curators")]
+    TooManyCurators,
+
+    /// NoUpdateAuthority
+    #[error("Mint extension update authority was revoked")]
+    NoUpdateAuthority,
+
+    /// NoFreezeAuthority
+    #[error("Mint has no freeze authority")]
+    NoFreezeAuthority,
+
+    /// SupplyNotFixed
+    #[error("Mint supply is not fixed, the mint authority must sign")]
+    SupplyNotFixed,
+
+    /// NotRentExempt
+    #[error("Mint extension account is not rent exempt")]
+    NotRentExempt,
+
+    /// ValueMismatch
+    #[error("Current symbol or name differs from the expected value")]
+    ValueMismatch,
+
+    /// InvalidEvent
+    #[error("Event data is malformed or of an unknown version")]
+    InvalidEvent,
+
+    /// RegistryAlreadyInitialized
+    #[error("Registry config has already been initialized")]
+    RegistryAlreadyInitialized,
+
+    /// InvalidValidationPolicy
+    #[error("Validation policy exceeds the limits of the program")]
+    InvalidValidationPolicy,
 }
 impl From<RegistryError> for ProgramError {
     fn from(e: RegistryError) -> Self {
@@ -64,13 +199,47 @@ impl PrintProgramError for RegistryError {
     {
         match self {
             RegistryError::InvalidInstruction => msg!("Invalid instruction"),
-            RegistryError::SymbolToLong => msg!("Symbol or Name is to long (<16)"),
+            RegistryError::SymbolToLong => msg!("Symbol or Name exceeds the configured maximum length"),
             RegistryError::NoAuthority => msg!("Must have authority to create mint"),
             RegistryError::NoMintAuthority => msg!("No mint_authority for mint"),
             RegistryError::Overflow => msg!("Operation overflowed"),
             RegistryError::NoRegistry => msg!("Mint Extension has not been registed"),
             RegistryError::AlreadRegistry => msg!("Mint Extension has already been registed"),
             RegistryError::TestError => msg!("TestError"),
+            RegistryError::InvalidMintExtensionAddress => msg!("Mint Extension address is not derived from the mint"),
+            RegistryError::InvalidMintOwner => msg!("Mint account is not owned by the SPL Token program"),
+            RegistryError::MintMismatch => msg!("Mint Extension does not belong to the mint"),
+            RegistryError::InvalidMintExtensionOwner => msg!("Mint Extension account is not owned by the registry program"),
+            RegistryError::TruncatedInstruction => msg!("Instruction data ended before all fields were read"),
+            RegistryError::InvalidStringLength => msg!("String length is longer than the remaining instruction data"),
+            RegistryError::InvalidUtf8 => msg!("String in instruction is not valid UTF-8"),
+            RegistryError::TrailingBytes => msg!("Instruction data has unexpected trailing bytes"),
+            RegistryError::MetadataTooLong => msg!("Metadata field is longer than allowed"),
+            RegistryError::TooManyTags => msg!("Too many tags"),
+            RegistryError::InvalidTag => msg!("Tag is empty or longer than allowed"),
+            RegistryError::InvalidCharacter => msg!("Symbol or Name contains a character that is not allowed"),
+            RegistryError::UnexpectedWhitespace => msg!("Symbol or Name has leading, trailing or repeated whitespace"),
+            RegistryError::ReservedSymbol => msg!("Symbol is reserved"),
+            RegistryError::EmptySymbol => msg!("Symbol is empty"),
+            RegistryError::InvalidSymbolIndexAddress => msg!("Symbol index account is not derived from the symbol"),
+            RegistryError::InvalidSymbolIndexOwner => msg!("Symbol index account is not owned by the program"),
+            RegistryError::SymbolTaken => msg!("Symbol is already registered to another mint"),
+            RegistryError::InvalidRegistryConfigAddress => msg!("Registry config account is not the one derived by the program"),
+            RegistryError::RegistryNotInitialized => msg!("Registry config has not been initialized"),
+            RegistryError::RegistryPaused => msg!("Registry is paused"),
+            RegistryError::NotAdmin => msg!("Signer is not the registry admin"),
+            RegistryError::NotPendingAdmin => msg!("Signer is not the pending registry admin"),
+            RegistryError::NotUpgradeAuthority => msg!("Signer is not the upgrade authority of the program"),
+            RegistryError::NotCurator => msg!("Key is not a registry curator"),
+            RegistryError::TooManyCurators => msg!("Registry has too many curators"),
+            RegistryError::NoUpdateAuthority => msg!("Mint extension update authority was revoked"),
+            RegistryError::NoFreezeAuthority => msg!("Mint has no freeze authority"),
+            RegistryError::SupplyNotFixed => msg!("Mint supply is not fixed, the mint authority must sign"),
+            RegistryError::NotRentExempt => msg!("Mint extension account is not rent exempt"),
+            RegistryError::ValueMismatch => msg!("Current symbol or name differs from the expected value"),
+            RegistryError::InvalidEvent => msg!("Event data is malformed or of an unknown version"),
+            RegistryError::RegistryAlreadyInitialized => msg!("Registry config has already been initialized"),
+            RegistryError::InvalidValidationPolicy => msg!("Validation policy exceeds the limits of the program"),
         }
     }
 }
\ No newline at end of file
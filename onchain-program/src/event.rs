@@ -0,0 +1,218 @@
+//! Events logged for indexers
+
+use crate::{error::RegistryError, state::RegistrationProof};
+#[cfg(not(target_os = "solana"))]
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{log::sol_log_data, program_error::ProgramError, pubkey::Pubkey};
+
+/// Version byte leading the data of every event.
+///
+/// Bumped whenever an event changes in a way older decoders cannot read;
+/// new events are only ever appended.
+pub const EVENT_VERSION: u8 = 1;
+
+/// Prefix the runtime puts in front of the base64 data logged by
+/// [emit](enum.RegistryEvent.html#method.emit).
+pub const EVENT_LOG_PREFIX: &str = "Program data: ";
+
+/// Changes made to a MintExtension, logged by the instructions making them.
+///
+/// The data is [EVENT_VERSION](constant.EVENT_VERSION.html) followed by the
+/// Borsh encoding, the variants being numbered from 0 in declaration order.
+/// It is logged with `sol_log_data`, which shows it base64 encoded behind
+/// [EVENT_LOG_PREFIX](constant.EVENT_LOG_PREFIX.html). Other programs log
+/// data the same way, so only the lines logged while this program runs
+/// should be decoded.
+///
+/// signer is the authority account that approved the change, the multisig
+/// itself for multisig authorities.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum RegistryEvent {
+    /// a mint was registered by RegisterMint or RegisterMintWithProof
+    Registered {
+        /// mint
+        mint: Pubkey,
+        /// symbol
+        symbol: String,
+        /// name
+        name: String,
+        /// proof of control over the mint
+        proof: RegistrationProof,
+        /// signer, the update authority of the new extension
+        signer: Pubkey,
+    },
+    /// symbol or name were changed by ModifyMint or UpdateMint
+    Modified {
+        /// mint
+        mint: Pubkey,
+        /// symbol before the change
+        old_symbol: String,
+        /// symbol after the change
+        new_symbol: String,
+        /// name before the change
+        old_name: String,
+        /// name after the change
+        new_name: String,
+        /// signer
+        signer: Pubkey,
+    },
+    /// the extension was closed by CloseMint
+    Closed {
+        /// mint
+        mint: Pubkey,
+        /// symbol released with the extension
+        old_symbol: String,
+        /// name of the closed extension
+        old_name: String,
+        /// signer
+        signer: Pubkey,
+    },
+    /// the update authority was handed over or revoked
+    AuthorityChanged {
+        /// mint
+        mint: Pubkey,
+        /// update authority before the change
+        old_authority: Pubkey,
+        /// update authority after the change, default once revoked
+        new_authority: Pubkey,
+        /// signer
+        signer: Pubkey,
+    },
+    /// a curator ran VerifyMint or UnverifyMint
+    Verified {
+        /// mint
+        mint: Pubkey,
+        /// verified before the instruction
+        old_verified: bool,
+        /// verified after the instruction
+        new_verified: bool,
+        /// signer, the curator
+        signer: Pubkey,
+    },
+}
+impl RegistryEvent {
+    /// Packs the event into the data that is logged.
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = vec![EVENT_VERSION];
+        buf.extend_from_slice(&borsh::to_vec(self).unwrap());
+        buf
+    }
+
+    /// Unpacks data written by [pack](#method.pack).
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&version, mut rest) = input.split_first().ok_or(RegistryError::InvalidEvent)?;
+        if version != EVENT_VERSION {
+            return Err(RegistryError::InvalidEvent.into());
+        }
+        let event = Self::deserialize(&mut rest).map_err(|_| RegistryError::InvalidEvent)?;
+        if !rest.is_empty() {
+            return Err(RegistryError::InvalidEvent.into());
+        }
+        Ok(event)
+    }
+
+    /// Logs the event.
+    pub fn emit(&self) {
+        sol_log_data(&[&self.pack()]);
+    }
+
+    /// Decodes a line of the transaction logs.
+    ///
+    /// Returns None for lines that do not carry logged data, and fails with
+    /// InvalidEvent for data that is not an event this crate knows. Only
+    /// built off chain, keeping base64 out of the program.
+    #[cfg(not(target_os = "solana"))]
+    pub fn decode_log(log: &str) -> Option<Result<Self, ProgramError>> {
+        let encoded = log.strip_prefix(EVENT_LOG_PREFIX)?;
+        Some(
+            STANDARD
+                .decode(encoded)
+                .map_err(|_| RegistryError::InvalidEvent.into())
+                .and_then(|data| Self::unpack(&data)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_pack() {
+        let mint = Pubkey::new_from_array([1u8; 32]);
+        let signer = Pubkey::new_from_array([2u8; 32]);
+        let events = vec![
+            RegistryEvent::Registered {
+                mint,
+                symbol: String::from("CZCOIN"),
+                name: String::from("CZ's COIN"),
+                proof: RegistrationProof::FreezeAuthority,
+                signer,
+            },
+            RegistryEvent::Modified {
+                mint,
+                old_symbol: String::from("CZCOIN"),
+                new_symbol: String::from("CZ"),
+                old_name: String::from("CZ's COIN"),
+                new_name: String::from("CZ's COIN"),
+                signer,
+            },
+            RegistryEvent::Closed {
+                mint,
+                old_symbol: String::from("CZ"),
+                old_name: String::from("CZ's COIN"),
+                signer,
+            },
+            RegistryEvent::AuthorityChanged {
+                mint,
+                old_authority: signer,
+                new_authority: Pubkey::default(),
+                signer,
+            },
+            RegistryEvent::Verified {
+                mint,
+                old_verified: false,
+                new_verified: true,
+                signer,
+            },
+        ];
+        for (variant, event) in events.into_iter().enumerate() {
+            let packed = event.pack();
+            assert_eq!(packed[0], EVENT_VERSION);
+            assert_eq!(packed[1], variant as u8);
+            assert_eq!(RegistryEvent::unpack(&packed), Ok(event.clone()));
+
+            let log = format!("{}{}", EVENT_LOG_PREFIX, STANDARD.encode(&packed));
+            assert_eq!(RegistryEvent::decode_log(&log), Some(Ok(event)));
+
+            let mut trailing = packed.clone();
+            trailing.push(0);
+            assert_eq!(RegistryEvent::unpack(&trailing), Err(RegistryError::InvalidEvent.into()));
+            assert_eq!(
+                RegistryEvent::unpack(&packed[..packed.len() - 1]),
+                Err(RegistryError::InvalidEvent.into())
+            );
+        }
+
+        // the layout of a version is fixed
+        let event = RegistryEvent::Verified { mint, old_verified: true, new_verified: false, signer };
+        let mut expect = vec![EVENT_VERSION, 4];
+        expect.extend_from_slice(mint.as_ref());
+        expect.extend_from_slice(&[1, 0]);
+        expect.extend_from_slice(signer.as_ref());
+        assert_eq!(event.pack(), expect);
+
+        let mut other_version = expect;
+        other_version[0] = EVENT_VERSION + 1;
+        assert_eq!(RegistryEvent::unpack(&other_version), Err(RegistryError::InvalidEvent.into()));
+        assert_eq!(RegistryEvent::unpack(&[]), Err(RegistryError::InvalidEvent.into()));
+        assert_eq!(RegistryEvent::unpack(&[EVENT_VERSION, 5]), Err(RegistryError::InvalidEvent.into()));
+
+        assert_eq!(RegistryEvent::decode_log("Program log: mint-registry: Instruction: RegisterMint"), None);
+        assert_eq!(
+            RegistryEvent::decode_log("Program data: not base64!"),
+            Some(Err(RegistryError::InvalidEvent.into()))
+        );
+    }
+}
@@ -1,37 +1,46 @@
 //! Instruction types
 
-use crate::error::RegistryError;
+use crate::{
+    error::RegistryError, find_mint_extension_address, find_registry_config_address,
+    find_symbol_index_address, state::RegistrationProof, validation::ValidationPolicy, Encoding,
+};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use solana_program::{
+    bpf_loader_upgradeable,
     instruction::{AccountMeta, Instruction},
     program_error::ProgramError,
     //program_option::COption,
     pubkey::Pubkey,
-    info,
-    //sysvar,
+    msg,
+    system_program,
+    sysvar,
 };
 //use std::convert::TryInto;
 use std::mem::size_of;
 use std::str::from_utf8;
-use std::convert::TryInto;
+use std::convert::TryFrom;
+
+/// Version byte leading Borsh encoded instruction data.
+///
+/// Legacy instructions start with their tag, which stays below this value.
+pub const BORSH_INSTRUCTION_VERSION: u8 = 0x80;
 
 /// Instructions supported by the mint-registry program.
+///
+/// Every instruction takes the [RegistryConfig](../state/struct.RegistryConfig.html)
+/// account first, ahead of the accounts listed by its builder.
+///
+/// In the Borsh encoding the variants are numbered from 0 in declaration order.
 #[repr(C)]
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub enum RegistryInstruction {
     /// Register  a new mint extension for a mint
     /// with a SYMBOL 
     /// and a NAME
-    /// echo should be a string which length < 16
+    /// each at most MAX_SYMBOL_NAME_LEN bytes of UTF-8
+    /// authorities, supply and decimals are read from the mint account
     RegisterMint {
-        /// mint_authority
-        mint_authority: Pubkey,
-        /// freeze_authority
-        freeze_authority: Pubkey,
-        /// supply
-        supply: u64,
-        /// decimals
-        decimals: u8,
-        /// mint is the address for a mint 
+        /// mint is the address for a mint, must match the mint account
         mint: Pubkey,
         /// symbol is a symbol for a mint
         symbol: String,
@@ -39,7 +48,9 @@ pub enum RegistryInstruction {
         name: String,
     },
 
-    /// CloseMint delete a Mint extension
+    /// CloseMint delete a Mint extension, the emptied account goes back to
+    /// the system program. Also takes extensions written at keypair addresses
+    /// by versions of the program before they were derived from the mint
     CloseMint ,
 
     /// Modify to modify an exist Mint extension
@@ -48,186 +59,818 @@ pub enum RegistryInstruction {
         symbol: String,
         /// name is a name for amint
         name: String,
+    },
+
+    /// MigrateExtension rewrite a Mint extension into the latest layout,
+    /// anyone may pay for the extra rent. It also zeroes the stale symbol and
+    /// name tails that the other instructions reject. Takes keypair address
+    /// extensions like CloseMint
+    MigrateExtension,
+
+    /// SetMetadataField set a metadata field of an exist Mint extension,
+    /// the account grows or shrinks to fit and the authority pays or gets back the rent
+    SetMetadataField {
+        /// field to set
+        field: MetadataField,
+        /// new value of the field
+        value: String,
+    },
+
+    /// ClearMetadataField clear a metadata field of an exist Mint extension
+    ClearMetadataField {
+        /// field to clear
+        field: MetadataField,
+    },
+
+    /// SetTags replace the tags of an exist Mint extension,
+    /// an empty list clears them
+    SetTags {
+        /// tags such as "stablecoin" or "wrapped"
+        tags: Vec<String>,
+    },
+
+    /// InitializeRegistry create the RegistryConfig,
+    /// signed by the upgrade authority of the program
+    InitializeRegistry {
+        /// admin of the registry
+        admin: Pubkey,
+        /// lamports charged by RegisterMint
+        registration_fee: u64,
+    },
+
+    /// SetPaused pause or resume every instruction but the admin ones
+    SetPaused {
+        /// paused
+        paused: bool,
+    },
+
+    /// SetRegistrationFee change the lamports charged by RegisterMint
+    SetRegistrationFee {
+        /// registration_fee
+        registration_fee: u64,
+    },
+
+    /// ProposeAdmin start handing the registry over to a new admin,
+    /// the default key cancels a pending transfer
+    ProposeAdmin {
+        /// new_admin
+        new_admin: Pubkey,
+    },
+
+    /// AcceptAdmin complete the transfer, signed by the pending admin
+    AcceptAdmin,
+
+    /// WithdrawFees move the collected fees out of the RegistryConfig
+    WithdrawFees,
+
+    /// AddCurator allow a key to verify mints
+    AddCurator {
+        /// curator
+        curator: Pubkey,
+    },
+
+    /// RemoveCurator take the right to verify mints away from a key
+    RemoveCurator {
+        /// curator
+        curator: Pubkey,
+    },
+
+    /// VerifyMint mark an exist Mint extension as verified, signed by a curator
+    VerifyMint,
+
+    /// UnverifyMint clear the verified flag of an exist Mint extension,
+    /// signed by a curator
+    UnverifyMint,
+
+    /// SetUpdateAuthority hand an exist Mint extension over to another
+    /// update authority
+    SetUpdateAuthority {
+        /// new_authority
+        new_authority: Pubkey,
+    },
+
+    /// RevokeUpdateAuthority freeze an exist Mint extension for good
+    RevokeUpdateAuthority,
+
+    /// RegisterMintWithProof register a new mint extension like RegisterMint,
+    /// proving control over the mint another way
+    RegisterMintWithProof {
+        /// mint
+        mint: Pubkey,
+        /// symbol
+        symbol: String,
+        /// name
+        name: String,
+        /// proof
+        proof: RegistrationProof,
+    },
+
+    /// UpdateMint modify an exist Mint extension like ModifyMint, changing
+    /// only the fields set in the update; an update setting neither symbol
+    /// nor name fails with InvalidInstruction
+    UpdateMint {
+        /// update
+        update: MintUpdate,
+    },
+
+    /// SyncMint refresh the supply, decimals and authorities an exist Mint
+    /// extension caches from the mint, and record the slot; anyone may call it
+    SyncMint,
+
+    /// SetValidationPolicy replace the rules applied to symbols and names,
+    /// the admin pays or gets back the rent as the config is resized
+    ///
+    /// The legacy encoding carries the policy in Borsh after the tag.
+    SetValidationPolicy {
+        /// policy
+        policy: ValidationPolicy,
+    },
+}
+
+/// Changes made by an [UpdateMint](enum.RegistryInstruction.html) instruction.
+///
+/// In the legacy encoding a presence bitmask leads the fields that are set.
+/// The expected values make the update a compare-and-swap: it fails with
+/// ValueMismatch unless the extension still holds them.
+#[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct MintUpdate {
+    /// new symbol, left alone when None
+    pub symbol: Option<String>,
+    /// new name, left alone when None
+    pub name: Option<String>,
+    /// symbol the extension must hold
+    pub expected_symbol: Option<String>,
+    /// name the extension must hold
+    pub expected_name: Option<String>,
+}
+impl MintUpdate {
+    const SYMBOL: u8 = 1;
+    const NAME: u8 = 1 << 1;
+    const EXPECTED_SYMBOL: u8 = 1 << 2;
+    const EXPECTED_NAME: u8 = 1 << 3;
+
+    /// Sets the new symbol.
+    pub fn symbol(mut self, symbol: String) -> Self {
+        self.symbol = Some(symbol);
+        self
+    }
+
+    /// Sets the new name.
+    pub fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Fails the update unless the current symbol is this one.
+    pub fn expect_symbol(mut self, symbol: String) -> Self {
+        self.expected_symbol = Some(symbol);
+        self
+    }
+
+    /// Fails the update unless the current name is this one.
+    pub fn expect_name(mut self, name: String) -> Self {
+        self.expected_name = Some(name);
+        self
+    }
+
+    fn unpack(input: &[u8]) -> Result<(Self, &[u8]), ProgramError> {
+        let (&mask, mut rest) = input.split_first().ok_or(RegistryError::TruncatedInstruction)?;
+        if mask & !(Self::SYMBOL | Self::NAME | Self::EXPECTED_SYMBOL | Self::EXPECTED_NAME) != 0 {
+            return Err(RegistryError::InvalidInstruction.into());
+        }
+        let mut update = Self::default();
+        for (bit, field) in [
+            (Self::SYMBOL, &mut update.symbol),
+            (Self::NAME, &mut update.name),
+            (Self::EXPECTED_SYMBOL, &mut update.expected_symbol),
+            (Self::EXPECTED_NAME, &mut update.expected_name),
+        ] {
+            if mask & bit != 0 {
+                let (value, next) = RegistryInstruction::unpack_string(rest)?;
+                *field = Some(value);
+                rest = next;
+            }
+        }
+        Ok((update, rest))
+    }
+
+    fn pack_into(&self, buf: &mut Vec<u8>) -> Result<(), ProgramError> {
+        let fields = [
+            (Self::SYMBOL, &self.symbol),
+            (Self::NAME, &self.name),
+            (Self::EXPECTED_SYMBOL, &self.expected_symbol),
+            (Self::EXPECTED_NAME, &self.expected_name),
+        ];
+        let mask = fields
+            .iter()
+            .filter(|(_, value)| value.is_some())
+            .fold(0, |mask, (bit, _)| mask | bit);
+        buf.push(mask);
+        for value in fields.iter().filter_map(|(_, value)| value.as_ref()) {
+            RegistryInstruction::pack_string(value, buf)?;
+        }
+        Ok(())
+    }
+}
+
+/// String fields of the [MintMetadata](../state/struct.MintMetadata.html) section.
+#[derive(Clone, Copy, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum MetadataField {
+    /// logo_uri
+    LogoUri,
+    /// website
+    Website,
+    /// description
+    Description,
+}
+impl MetadataField {
+    fn unpack(input: &[u8]) -> Result<(Self, &[u8]), ProgramError> {
+        let (&field, rest) = input.split_first().ok_or(RegistryError::TruncatedInstruction)?;
+        let field = match field {
+            0 => Self::LogoUri,
+            1 => Self::Website,
+            2 => Self::Description,
+            _ => return Err(RegistryError::InvalidInstruction.into()),
+        };
+        Ok((field, rest))
     }
 }
 
 
 impl RegistryInstruction {
     /// Unpacks a byte buffer into a [RegistryInstruction](enum.RegistryInstruction.html).
+    ///
+    /// Never panics: malformed input is reported with a dedicated
+    /// [RegistryError](../error/enum.RegistryError.html).
     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack_versioned(input).map(|(instruction, _)| instruction)
+    }
+
+    /// Unpacks a byte buffer in either encoding, telling which one was used.
+    pub fn unpack_versioned(input: &[u8]) -> Result<(Self, Encoding), ProgramError> {
+        match input.split_first() {
+            Some((&BORSH_INSTRUCTION_VERSION, rest)) => Ok((Self::unpack_borsh(rest)?, Encoding::Borsh)),
+            _ => Ok((Self::unpack_legacy(input)?, Encoding::Legacy)),
+        }
+    }
+
+    /// Borsh reports truncated and malformed data alike, so both are
+    /// returned as InvalidInstruction.
+    fn unpack_borsh(mut input: &[u8]) -> Result<Self, ProgramError> {
+        let instruction = Self::deserialize(&mut input)
+            .map_err(|_| RegistryError::InvalidInstruction)?;
+        if !input.is_empty() {
+            return Err(RegistryError::TrailingBytes.into());
+        }
+        Ok(instruction)
+    }
+
+    fn unpack_legacy(input: &[u8]) -> Result<Self, ProgramError> {
         use RegistryError::InvalidInstruction;
 
-        let (&tag, rest) = input.split_first().ok_or(InvalidInstruction)?;
-        Ok(match tag { //RegisterMint
+        let (&tag, rest) = input.split_first().ok_or(RegistryError::TruncatedInstruction)?;
+        let (instruction, rest) = match tag { //RegisterMint
             1 => {
-                let (mint_authority, rest) = Self::unpack_pubkey(rest)?;
-                let (freeze_authority, rest) = Self::unpack_pubkey(rest)?;
-                let (supply, rest) = Self::unpack_u64(rest)?;
-                let (&decimals, rest) = rest.split_first().ok_or(InvalidInstruction)?; 
                 let (mint, rest) = Self::unpack_pubkey(rest)?;
-                let (&len, rest) = rest.split_first().ok_or(InvalidInstruction)?;
-                let (symbol_buf, rest) = rest.split_at(len.into());
-                let symbol = String::from(from_utf8(symbol_buf).unwrap());
-                let (&len, rest) = rest.split_first().ok_or(InvalidInstruction)?;
-                let (name_buf, _rest) = rest.split_at(len.into());
-                let name = String::from(from_utf8(name_buf).unwrap());
-                Self::RegisterMint{
-                    mint_authority,
-                    freeze_authority,
-                    supply,
-                    decimals,
+                let (symbol, rest) = Self::unpack_string(rest)?;
+                let (name, rest) = Self::unpack_string(rest)?;
+                (Self::RegisterMint{
                     mint,
                     symbol,
                     name,
-                }
+                }, rest)
             },
-            2 => Self::CloseMint,
+            2 => (Self::CloseMint, rest),
             3 => {
-                let (&len, rest) = rest.split_first().ok_or(InvalidInstruction)?;
-                let (symbol_buf, rest) = rest.split_at(len.into());
-                let symbol = String::from(from_utf8(symbol_buf).unwrap());
-                let (&len, rest) = rest.split_first().ok_or(InvalidInstruction)?;
-                let (name_buf, _rest) = rest.split_at(len.into());
-                let name = String::from(from_utf8(name_buf).unwrap());
-                Self::ModifyMint{
+                let (symbol, rest) = Self::unpack_string(rest)?;
+                let (name, rest) = Self::unpack_string(rest)?;
+                (Self::ModifyMint{
                     symbol,
                     name,
+                }, rest)
+            }
+            4 => (Self::MigrateExtension, rest),
+            5 => {
+                let (field, rest) = MetadataField::unpack(rest)?;
+                let (value, rest) = Self::unpack_string(rest)?;
+                (Self::SetMetadataField{
+                    field,
+                    value,
+                }, rest)
+            }
+            6 => {
+                let (field, rest) = MetadataField::unpack(rest)?;
+                (Self::ClearMetadataField{field}, rest)
+            }
+            7 => {
+                let (&count, mut rest) = rest.split_first().ok_or(RegistryError::TruncatedInstruction)?;
+                let mut tags = Vec::with_capacity(usize::from(count));
+                for _ in 0..count {
+                    let (tag, next) = Self::unpack_string(rest)?;
+                    tags.push(tag);
+                    rest = next;
                 }
+                (Self::SetTags{tags}, rest)
+            }
+            8 => {
+                let (admin, rest) = Self::unpack_pubkey(rest)?;
+                let (registration_fee, rest) = Self::unpack_u64(rest)?;
+                (Self::InitializeRegistry{
+                    admin,
+                    registration_fee,
+                }, rest)
+            }
+            9 => {
+                let (&paused, rest) = rest.split_first().ok_or(RegistryError::TruncatedInstruction)?;
+                let paused = match paused {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(RegistryError::InvalidInstruction.into()),
+                };
+                (Self::SetPaused{paused}, rest)
+            }
+            10 => {
+                let (registration_fee, rest) = Self::unpack_u64(rest)?;
+                (Self::SetRegistrationFee{registration_fee}, rest)
+            }
+            11 => {
+                let (new_admin, rest) = Self::unpack_pubkey(rest)?;
+                (Self::ProposeAdmin{new_admin}, rest)
+            }
+            12 => (Self::AcceptAdmin, rest),
+            13 => (Self::WithdrawFees, rest),
+            14 => {
+                let (curator, rest) = Self::unpack_pubkey(rest)?;
+                (Self::AddCurator{curator}, rest)
+            }
+            15 => {
+                let (curator, rest) = Self::unpack_pubkey(rest)?;
+                (Self::RemoveCurator{curator}, rest)
+            }
+            16 => (Self::VerifyMint, rest),
+            17 => (Self::UnverifyMint, rest),
+            18 => {
+                let (new_authority, rest) = Self::unpack_pubkey(rest)?;
+                (Self::SetUpdateAuthority{new_authority}, rest)
+            }
+            19 => (Self::RevokeUpdateAuthority, rest),
+            20 => {
+                let (mint, rest) = Self::unpack_pubkey(rest)?;
+                let (symbol, rest) = Self::unpack_string(rest)?;
+                let (name, rest) = Self::unpack_string(rest)?;
+                let (&proof, rest) = rest.split_first().ok_or(RegistryError::TruncatedInstruction)?;
+                let proof = match proof {
+                    0 => RegistrationProof::MintAuthority,
+                    1 => RegistrationProof::FreezeAuthority,
+                    2 => RegistrationProof::AdminAttestation,
+                    _ => return Err(InvalidInstruction.into()),
+                };
+                (Self::RegisterMintWithProof{
+                    mint,
+                    symbol,
+                    name,
+                    proof,
+                }, rest)
+            }
+            21 => {
+                let (update, rest) = MintUpdate::unpack(rest)?;
+                (Self::UpdateMint{update}, rest)
+            }
+            22 => (Self::SyncMint, rest),
+            23 => {
+                let mut rest = rest;
+                let policy = ValidationPolicy::deserialize(&mut rest)
+                    .map_err(|_| RegistryError::InvalidInstruction)?;
+                (Self::SetValidationPolicy{policy}, rest)
             }
             _ => return Err(RegistryError::InvalidInstruction.into()),
-        })
+        };
+        if !rest.is_empty() {
+            return Err(RegistryError::TrailingBytes.into());
+        }
+        Ok(instruction)
     }
 
     fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
         if input.len() >= 32 {
             let (key, rest) = input.split_at(32);
-            let pk = Pubkey::new(key);
+            let pk = Pubkey::try_from(key).map_err(|_| RegistryError::TruncatedInstruction)?;
             Ok((pk, rest))
         } else {
-            Err(RegistryError::InvalidInstruction.into())
+            Err(RegistryError::TruncatedInstruction.into())
         }
     }
 
     fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
-        if input.len() >= 8 {
-            let (amount, rest) = input.split_at(8);
-            let amount = amount
-                .get(..8)
-                .and_then(|slice| slice.try_into().ok())
-                .map(u64::from_le_bytes)
-                .ok_or(RegistryError::InvalidInstruction)?;
-            Ok((amount, rest))
-        } else {
-            Err(RegistryError::InvalidInstruction.into())
+        if input.len() < 8 {
+            return Err(RegistryError::TruncatedInstruction.into());
         }
+        let (value, rest) = input.split_at(8);
+        let value = <[u8; 8]>::try_from(value).map_err(|_| RegistryError::TruncatedInstruction)?;
+        Ok((u64::from_le_bytes(value), rest))
+    }
+
+    fn unpack_string(input: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+        let (&len, rest) = input.split_first().ok_or(RegistryError::TruncatedInstruction)?;
+        let len = usize::from(len);
+        if rest.len() < len {
+            return Err(RegistryError::InvalidStringLength.into());
+        }
+        let (buf, rest) = rest.split_at(len);
+        let string = from_utf8(buf).map_err(|_| RegistryError::InvalidUtf8)?;
+        Ok((String::from(string), rest))
+    }
+
+    fn pack_string(value: &str, buf: &mut Vec<u8>) -> Result<(), ProgramError> {
+        buf.push(Self::pack_len(value.len())?);
+        buf.extend_from_slice(value.as_bytes());
+        Ok(())
+    }
+
+    /// Lengths are packed in a single byte, which longer values would wrap.
+    fn pack_len(len: usize) -> Result<u8, ProgramError> {
+        u8::try_from(len).map_err(|_| RegistryError::InvalidInstruction.into())
+    }
+
+    /// Packs a [RegistryInstruction](enum.RegistryInstruction.html) into a
+    /// version byte followed by its Borsh encoding.
+    pub fn pack_borsh(&self) -> Vec<u8> {
+        let mut buf = vec![BORSH_INSTRUCTION_VERSION];
+        buf.extend_from_slice(&borsh::to_vec(self).unwrap());
+        buf
     }
 
     /// Packs a [RegistryInstruction](enum.RegistryInstruction.html) into a byte buffer.
-    pub fn pack(&self) -> Vec<u8> {
+    ///
+    /// Fails with InvalidInstruction when a string or the tag list is longer
+    /// than its length byte can tell.
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
         let mut buf : Vec<u8>;
         let self_len= size_of::<Self>();
         match self {
-            &Self::RegisterMint {
-                ref mint_authority,
-                ref freeze_authority,
-                ref supply,
-                ref decimals,
-                ref mint,
-                ref symbol,
-                ref name,
+            Self::RegisterMint {
+                mint,
+                symbol,
+                name,
             } => {
                 buf = Vec::with_capacity(self_len+1+1+1);
                 buf.push(1); // tag
-                buf.extend_from_slice(mint_authority.as_ref());
-                buf.extend_from_slice(freeze_authority.as_ref());
-                buf.extend_from_slice(&supply.to_le_bytes());
-                buf.push(*decimals);
                 buf.extend_from_slice(mint.as_ref());
-                buf.push(symbol.len() as u8);
-                buf.extend_from_slice(symbol.as_bytes());
-                buf.push(name.len() as u8);
-                buf.extend_from_slice(name.as_ref());
+                Self::pack_string(symbol, &mut buf)?;
+                Self::pack_string(name, &mut buf)?;
             }
             Self::CloseMint => {
                 buf = Vec::with_capacity(self_len);
                 buf.push(2); //tag
             }
-            &Self::ModifyMint {
-                ref symbol,
-                ref name,
+            Self::ModifyMint {
+                symbol,
+                name,
             } => {
                 buf = Vec::with_capacity(self_len+1+1+1);
                 buf.push(3); // tag
-                buf.push(symbol.len() as u8);
-                buf.extend_from_slice(symbol.as_bytes());
-                buf.push(name.len() as u8);
-                buf.extend_from_slice(name.as_ref());
+                Self::pack_string(symbol, &mut buf)?;
+                Self::pack_string(name, &mut buf)?;
+            }
+            Self::MigrateExtension => {
+                buf = Vec::with_capacity(self_len);
+                buf.push(4); //tag
+            }
+            Self::SetMetadataField {
+                field,
+                value,
+            } => {
+                buf = Vec::with_capacity(1+1+1+value.len());
+                buf.push(5); // tag
+                buf.push(*field as u8);
+                Self::pack_string(value, &mut buf)?;
+            }
+            Self::ClearMetadataField { field } => {
+                buf = vec![6, *field as u8]; // tag, field
+            }
+            Self::SetTags { tags } => {
+                buf = Vec::with_capacity(self_len);
+                buf.push(7); // tag
+                buf.push(Self::pack_len(tags.len())?);
+                for tag in tags {
+                    Self::pack_string(tag, &mut buf)?;
+                }
+            }
+            Self::InitializeRegistry {
+                admin,
+                registration_fee,
+            } => {
+                buf = Vec::with_capacity(1+32+8);
+                buf.push(8); // tag
+                buf.extend_from_slice(admin.as_ref());
+                buf.extend_from_slice(&registration_fee.to_le_bytes());
+            }
+            Self::SetPaused { paused } => {
+                buf = vec![9, *paused as u8]; // tag, paused
+            }
+            Self::SetRegistrationFee { registration_fee } => {
+                buf = Vec::with_capacity(1+8);
+                buf.push(10); // tag
+                buf.extend_from_slice(&registration_fee.to_le_bytes());
+            }
+            Self::ProposeAdmin { new_admin } => {
+                buf = Vec::with_capacity(1+32);
+                buf.push(11); // tag
+                buf.extend_from_slice(new_admin.as_ref());
+            }
+            Self::AcceptAdmin => {
+                buf = vec![12]; // tag
+            }
+            Self::WithdrawFees => {
+                buf = vec![13]; // tag
+            }
+            Self::AddCurator { curator } => {
+                buf = Vec::with_capacity(1+32);
+                buf.push(14); // tag
+                buf.extend_from_slice(curator.as_ref());
+            }
+            Self::RemoveCurator { curator } => {
+                buf = Vec::with_capacity(1+32);
+                buf.push(15); // tag
+                buf.extend_from_slice(curator.as_ref());
+            }
+            Self::VerifyMint => {
+                buf = vec![16]; // tag
+            }
+            Self::UnverifyMint => {
+                buf = vec![17]; // tag
+            }
+            Self::SetUpdateAuthority { new_authority } => {
+                buf = Vec::with_capacity(1+32);
+                buf.push(18); // tag
+                buf.extend_from_slice(new_authority.as_ref());
+            }
+            Self::RevokeUpdateAuthority => {
+                buf = vec![19]; // tag
+            }
+            Self::RegisterMintWithProof {
+                mint,
+                symbol,
+                name,
+                proof,
+            } => {
+                buf = Vec::with_capacity(1+32+1+symbol.len()+1+name.len()+1);
+                buf.push(20); // tag
+                buf.extend_from_slice(mint.as_ref());
+                Self::pack_string(symbol, &mut buf)?;
+                Self::pack_string(name, &mut buf)?;
+                buf.push(*proof as u8);
+            }
+            Self::UpdateMint { update } => {
+                buf = Vec::with_capacity(self_len);
+                buf.push(21); // tag
+                update.pack_into(&mut buf)?;
+            }
+            Self::SyncMint => {
+                buf = Vec::with_capacity(self_len);
+                buf.push(22); // tag
+            }
+            Self::SetValidationPolicy { policy } => {
+                buf = vec![23]; // tag
+                buf.extend_from_slice(&borsh::to_vec(policy).unwrap());
             }
         };
-        buf
+        Ok(buf)
     }    
 }
 
 /// register_mint_instruction create a RegisterMint instruction
+///
+/// The MintExtension account is derived from the mint with
+/// [find_mint_extension_address](../fn.find_mint_extension_address.html)
+/// and created by the program, funded by the payer, as is the
+/// [SymbolIndex](../state/struct.SymbolIndex.html) claiming the symbol.
+///
+/// policy is the [ValidationPolicy](../validation/struct.ValidationPolicy.html)
+/// of the registry config. The index is derived from the symbol as the policy
+/// normalises it, so a symbol the policy trims reaches the index the program
+/// claims. The same goes for the other builders taking a policy.
+///
+/// When the mint authority is an SPL Token multisig, signer_pubkeys lists M of
+/// its signers, the first of which pays in its place. The same goes for the
+/// other builders taking signer_pubkeys.
 pub fn register_mint_instruction(
     program_id: &Pubkey,
-    mint_authority_key: &Pubkey,
-    freeze_authority_key: &Pubkey,
-    supply: u64,
-    decimals: u8,
+    policy: &ValidationPolicy,
     mint_key: &Pubkey,
     symbol: String,
     name: String,
     payer_key: &Pubkey,
-    mintext_key: &Pubkey,
     signer_pubkeys: &[&Pubkey],
 ) -> Result<Instruction, ProgramError> {
-    info!("register_mint_instruction");
+    msg!("register_mint_instruction");
+    let (symbol_index_key, _) = find_symbol_index_address(program_id, policy, &symbol);
     let data = RegistryInstruction::RegisterMint { 
-        mint_authority: *mint_authority_key,
-        freeze_authority: *freeze_authority_key,
-        supply:supply,
-        decimals:decimals,
         mint:*mint_key,
         symbol,
         name,
-     }.pack();
+     }.pack()?;
+    Ok(register_instruction(program_id, mint_key, symbol_index_key, data, payer_key, signer_pubkeys))
+}
+
+/// register_mint_with_freeze_authority_instruction create a RegisterMintWithProof
+/// instruction signed by the freeze authority of a mint whose supply is fixed
+pub fn register_mint_with_freeze_authority_instruction(
+    program_id: &Pubkey,
+    policy: &ValidationPolicy,
+    mint_key: &Pubkey,
+    symbol: String,
+    name: String,
+    freeze_authority_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    msg!("register_mint_with_freeze_authority_instruction");
+    let (symbol_index_key, _) = find_symbol_index_address(program_id, policy, &symbol);
+    let data = RegistryInstruction::RegisterMintWithProof {
+        mint: *mint_key,
+        symbol,
+        name,
+        proof: RegistrationProof::FreezeAuthority,
+    }.pack()?;
+    Ok(register_instruction(program_id, mint_key, symbol_index_key, data, freeze_authority_key, signer_pubkeys))
+}
 
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*mint_key, false));
-    accounts.push(AccountMeta::new(*payer_key, true));
-    accounts.push(AccountMeta::new(*mintext_key, true));
+/// register_mint_with_attestation_instruction create a RegisterMintWithProof
+/// instruction co-signed by the registry admin
+///
+/// The payer becomes the update authority of the Mint extension.
+pub fn register_mint_with_attestation_instruction(
+    program_id: &Pubkey,
+    policy: &ValidationPolicy,
+    mint_key: &Pubkey,
+    symbol: String,
+    name: String,
+    payer_key: &Pubkey,
+    admin_key: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("register_mint_with_attestation_instruction");
+    let (symbol_index_key, _) = find_symbol_index_address(program_id, policy, &symbol);
+    let data = RegistryInstruction::RegisterMintWithProof {
+        mint: *mint_key,
+        symbol,
+        name,
+        proof: RegistrationProof::AdminAttestation,
+    }.pack()?;
+    let mut instruction = register_instruction(program_id, mint_key, symbol_index_key, data, payer_key, &[]);
+    instruction.accounts.push(AccountMeta::new_readonly(*admin_key, true));
+    Ok(instruction)
+}
+
+/// Accounts shared by the instructions registering a mint
+fn register_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    symbol_index_key: Pubkey,
+    data: Vec<u8>,
+    payer_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Instruction {
+    let (mintext_key, _) = find_mint_extension_address(program_id, mint_key);
+    let (config_key, _) = find_registry_config_address(program_id);
+
+    let mut accounts = Vec::with_capacity(7 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(config_key, false));
+    accounts.push(AccountMeta::new_readonly(*mint_key, false));
+    accounts.push(AccountMeta::new(*payer_key, signer_pubkeys.is_empty()));
+    accounts.push(AccountMeta::new(mintext_key, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new(symbol_index_key, false));
     for signer_pubkey in signer_pubkeys.iter() {
-        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+        accounts.push(AccountMeta::new(**signer_pubkey, true));
     }
 
-    Ok(Instruction {
+    Instruction {
         program_id: *program_id,
         accounts,
         data,
-    })
+    }
 }
 
 /// modify_mint_instruction modify a RegisterMint instruction
+///
+/// old_symbol is the symbol currently registered; its
+/// [SymbolIndex](../state/struct.SymbolIndex.html) is closed and the one of
+/// the new symbol claimed, funded by the payer.
+///
+/// Built as an UpdateMint setting both fields; the program still accepts the
+/// ModifyMint encoding sent by older clients.
+#[allow(clippy::too_many_arguments)]
 pub fn modify_mint_instruction(
     program_id: &Pubkey,
+    policy: &ValidationPolicy,
     mint_key: &Pubkey,
+    old_symbol: &str,
     symbol: String,
     name: String,
     payer_key: &Pubkey,
-    mintext_key: &Pubkey,
     signer_pubkeys: &[&Pubkey],
 ) -> Result<Instruction, ProgramError> {
-    info!("modify_mint_instruction");
-    let data = RegistryInstruction::ModifyMint { 
-        symbol,
-        name,
-     }.pack();
+    msg!("modify_mint_instruction");
+    let update = MintUpdate::default().symbol(symbol).name(name);
+    update_mint_instruction(program_id, policy, mint_key, old_symbol, update, payer_key, signer_pubkeys)
+}
 
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*mint_key, false));
-    accounts.push(AccountMeta::new(*payer_key, true));
-    accounts.push(AccountMeta::new(*mintext_key, true));
+/// update_mint_instruction create an UpdateMint instruction
+///
+/// The update is built with the helpers of [MintUpdate](struct.MintUpdate.html):
+///
+/// ```ignore
+/// let update = MintUpdate::default()
+///     .name(String::from("USD Coin"))
+///     .expect_name(String::from("USDC"));
+/// update_mint_instruction(&program_id, &policy, &mint_key, "USDC", update, &payer_key, &[])?;
+/// ```
+///
+/// current_symbol is the symbol currently registered, whose
+/// [SymbolIndex](../state/struct.SymbolIndex.html) is only closed when the
+/// update sets a new symbol.
+pub fn update_mint_instruction(
+    program_id: &Pubkey,
+    policy: &ValidationPolicy,
+    mint_key: &Pubkey,
+    current_symbol: &str,
+    update: MintUpdate,
+    payer_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    msg!("update_mint_instruction");
+    let symbol = update.symbol.as_deref().unwrap_or(current_symbol);
+    let (old_symbol_index_key, _) = find_symbol_index_address(program_id, policy, current_symbol);
+    let (symbol_index_key, _) = find_symbol_index_address(program_id, policy, symbol);
+    let data = RegistryInstruction::UpdateMint { update }.pack()?;
+    Ok(modify_instruction(program_id, mint_key, old_symbol_index_key, symbol_index_key, data, payer_key, signer_pubkeys))
+}
+
+/// Accounts shared by the instructions changing symbol or name
+///
+/// Both symbol indexes are listed even when the symbol stays, as the
+/// multisig signers follow them at fixed positions.
+fn modify_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    old_symbol_index_key: Pubkey,
+    symbol_index_key: Pubkey,
+    data: Vec<u8>,
+    payer_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Instruction {
+    let (mintext_key, _) = find_mint_extension_address(program_id, mint_key);
+    let (config_key, _) = find_registry_config_address(program_id);
+
+    let mut accounts = Vec::with_capacity(8 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new_readonly(config_key, false));
+    accounts.push(AccountMeta::new_readonly(*mint_key, false));
+    accounts.push(AccountMeta::new(*payer_key, signer_pubkeys.is_empty()));
+    accounts.push(AccountMeta::new(mintext_key, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new(old_symbol_index_key, false));
+    accounts.push(AccountMeta::new(symbol_index_key, false));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new(**signer_pubkey, true));
+    }
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// close_mint_instruction create a CloseMint instruction
+///
+/// symbol is the symbol currently registered, whose
+/// [SymbolIndex](../state/struct.SymbolIndex.html) is closed as well. The
+/// rent of both accounts goes to the destination.
+pub fn close_mint_instruction(
+    program_id: &Pubkey,
+    policy: &ValidationPolicy,
+    mint_key: &Pubkey,
+    symbol: &str,
+    destination_key: &Pubkey,
+    authority_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    msg!("close_mint_instruction");
+    let data = RegistryInstruction::CloseMint.pack()?;
+    let (mintext_key, _) = find_mint_extension_address(program_id, mint_key);
+    let (symbol_index_key, _) = find_symbol_index_address(program_id, policy, symbol);
+
+    let (config_key, _) = find_registry_config_address(program_id);
+
+    let mut accounts = Vec::with_capacity(6 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new_readonly(config_key, false));
+    accounts.push(AccountMeta::new_readonly(*mint_key, false));
+    accounts.push(AccountMeta::new_readonly(*authority_key, signer_pubkeys.is_empty()));
+    accounts.push(AccountMeta::new(mintext_key, false));
+    accounts.push(AccountMeta::new(symbol_index_key, false));
+    accounts.push(AccountMeta::new(*destination_key, false));
     for signer_pubkey in signer_pubkeys.iter() {
         accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
     }
@@ -239,24 +882,189 @@ pub fn modify_mint_instruction(
     })
 }
 
-/// close_mint_instruction modify a RegisterMint instruction
-pub fn close_mint_instruction(
+/// sync_mint_instruction create a SyncMint instruction
+///
+/// The payer only pays when the extension has to be migrated to the latest
+/// layout first.
+pub fn sync_mint_instruction(
     program_id: &Pubkey,
     mint_key: &Pubkey,
     payer_key: &Pubkey,
-    mintext_key: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("sync_mint_instruction");
+    let data = RegistryInstruction::SyncMint.pack()?;
+    let (mintext_key, _) = find_mint_extension_address(program_id, mint_key);
+
+    let (config_key, _) = find_registry_config_address(program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(config_key, false),
+        AccountMeta::new_readonly(*mint_key, false),
+        AccountMeta::new(*payer_key, true),
+        AccountMeta::new(mintext_key, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// migrate_extension_instruction create a MigrateExtension instruction
+pub fn migrate_extension_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    payer_key: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("migrate_extension_instruction");
+    let data = RegistryInstruction::MigrateExtension.pack()?;
+    let (mintext_key, _) = find_mint_extension_address(program_id, mint_key);
+
+    let (config_key, _) = find_registry_config_address(program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(config_key, false),
+        AccountMeta::new_readonly(*mint_key, false),
+        AccountMeta::new(*payer_key, true),
+        AccountMeta::new(mintext_key, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// set_metadata_field_instruction create a SetMetadataField instruction
+pub fn set_metadata_field_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    field: MetadataField,
+    value: String,
+    payer_key: &Pubkey,
     signer_pubkeys: &[&Pubkey],
 ) -> Result<Instruction, ProgramError> {
-    info!("close_mint_instruction");
-    let data = RegistryInstruction::CloseMint.pack();
+    msg!("set_metadata_field_instruction");
+    let data = RegistryInstruction::SetMetadataField { field, value }.pack()?;
+    Ok(extension_instruction(program_id, mint_key, data, payer_key, signer_pubkeys))
+}
 
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*mintext_key, true));
-    accounts.push(AccountMeta::new(*payer_key, true));
-    accounts.push(AccountMeta::new(*mint_key, false));
+/// clear_metadata_field_instruction create a ClearMetadataField instruction
+pub fn clear_metadata_field_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    field: MetadataField,
+    payer_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    msg!("clear_metadata_field_instruction");
+    let data = RegistryInstruction::ClearMetadataField { field }.pack()?;
+    Ok(extension_instruction(program_id, mint_key, data, payer_key, signer_pubkeys))
+}
+
+/// set_tags_instruction create a SetTags instruction
+pub fn set_tags_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    tags: Vec<String>,
+    payer_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    msg!("set_tags_instruction");
+    let data = RegistryInstruction::SetTags { tags }.pack()?;
+    Ok(extension_instruction(program_id, mint_key, data, payer_key, signer_pubkeys))
+}
+
+/// set_update_authority_instruction create a SetUpdateAuthority instruction
+pub fn set_update_authority_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    new_authority: &Pubkey,
+    authority_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    msg!("set_update_authority_instruction");
+    let data = RegistryInstruction::SetUpdateAuthority { new_authority: *new_authority }.pack()?;
+    Ok(extension_instruction(program_id, mint_key, data, authority_key, signer_pubkeys))
+}
+
+/// revoke_update_authority_instruction create a RevokeUpdateAuthority instruction
+///
+/// Nobody can modify or close the Mint extension afterwards.
+pub fn revoke_update_authority_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    authority_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    msg!("revoke_update_authority_instruction");
+    let data = RegistryInstruction::RevokeUpdateAuthority.pack()?;
+    Ok(extension_instruction(program_id, mint_key, data, authority_key, signer_pubkeys))
+}
+
+/// Accounts shared by the instructions rewriting an extension on behalf of
+/// its update authority, which also pays for any migration
+fn extension_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    data: Vec<u8>,
+    payer_key: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Instruction {
+    let (mintext_key, _) = find_mint_extension_address(program_id, mint_key);
+    let (config_key, _) = find_registry_config_address(program_id);
+
+    let mut accounts = Vec::with_capacity(6 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new_readonly(config_key, false));
+    accounts.push(AccountMeta::new_readonly(*mint_key, false));
+    accounts.push(AccountMeta::new(*payer_key, signer_pubkeys.is_empty()));
+    accounts.push(AccountMeta::new(mintext_key, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
     for signer_pubkey in signer_pubkeys.iter() {
-        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+        accounts.push(AccountMeta::new(**signer_pubkey, true));
+    }
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
     }
+}
+
+/// initialize_registry_instruction create an InitializeRegistry instruction
+///
+/// The upgrade authority of the program signs and pays for the RegistryConfig,
+/// so nobody can initialize the registry ahead of its deployer.
+pub fn initialize_registry_instruction(
+    program_id: &Pubkey,
+    upgrade_authority_key: &Pubkey,
+    admin: &Pubkey,
+    registration_fee: u64,
+) -> Result<Instruction, ProgramError> {
+    msg!("initialize_registry_instruction");
+    let data = RegistryInstruction::InitializeRegistry {
+        admin: *admin,
+        registration_fee,
+    }.pack()?;
+    let (config_key, _) = find_registry_config_address(program_id);
+    let (program_data_key, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+
+    let accounts = vec![
+        AccountMeta::new(config_key, false),
+        AccountMeta::new(*upgrade_authority_key, true),
+        AccountMeta::new_readonly(program_data_key, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
 
     Ok(Instruction {
         program_id: *program_id,
@@ -265,18 +1073,206 @@ pub fn close_mint_instruction(
     })
 }
 
+/// set_paused_instruction create a SetPaused instruction
+pub fn set_paused_instruction(
+    program_id: &Pubkey,
+    admin_key: &Pubkey,
+    paused: bool,
+) -> Result<Instruction, ProgramError> {
+    msg!("set_paused_instruction");
+    let data = RegistryInstruction::SetPaused { paused }.pack()?;
+    Ok(admin_instruction(program_id, admin_key, data, vec![]))
+}
+
+/// set_registration_fee_instruction create a SetRegistrationFee instruction
+pub fn set_registration_fee_instruction(
+    program_id: &Pubkey,
+    admin_key: &Pubkey,
+    registration_fee: u64,
+) -> Result<Instruction, ProgramError> {
+    msg!("set_registration_fee_instruction");
+    let data = RegistryInstruction::SetRegistrationFee { registration_fee }.pack()?;
+    Ok(admin_instruction(program_id, admin_key, data, vec![]))
+}
+
+/// propose_admin_instruction create a ProposeAdmin instruction
+pub fn propose_admin_instruction(
+    program_id: &Pubkey,
+    admin_key: &Pubkey,
+    new_admin: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("propose_admin_instruction");
+    let data = RegistryInstruction::ProposeAdmin { new_admin: *new_admin }.pack()?;
+    Ok(admin_instruction(program_id, admin_key, data, vec![]))
+}
+
+/// accept_admin_instruction create an AcceptAdmin instruction
+pub fn accept_admin_instruction(
+    program_id: &Pubkey,
+    pending_admin_key: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("accept_admin_instruction");
+    let data = RegistryInstruction::AcceptAdmin.pack()?;
+    Ok(admin_instruction(program_id, pending_admin_key, data, vec![]))
+}
+
+/// withdraw_fees_instruction create a WithdrawFees instruction
+///
+/// Lamports above the rent exempt minimum of the RegistryConfig go to the destination.
+pub fn withdraw_fees_instruction(
+    program_id: &Pubkey,
+    admin_key: &Pubkey,
+    destination_key: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("withdraw_fees_instruction");
+    let data = RegistryInstruction::WithdrawFees.pack()?;
+    Ok(admin_instruction(program_id, admin_key, data, vec![
+        AccountMeta::new(*destination_key, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ]))
+}
+
+/// add_curator_instruction create an AddCurator instruction
+///
+/// The admin pays for the room the curator takes in the RegistryConfig.
+pub fn add_curator_instruction(
+    program_id: &Pubkey,
+    admin_key: &Pubkey,
+    curator: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("add_curator_instruction");
+    let data = RegistryInstruction::AddCurator { curator: *curator }.pack()?;
+    Ok(resize_config_instruction(program_id, admin_key, data))
+}
+
+/// remove_curator_instruction create a RemoveCurator instruction
+///
+/// The rent freed in the RegistryConfig goes back to the admin.
+pub fn remove_curator_instruction(
+    program_id: &Pubkey,
+    admin_key: &Pubkey,
+    curator: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("remove_curator_instruction");
+    let data = RegistryInstruction::RemoveCurator { curator: *curator }.pack()?;
+    Ok(resize_config_instruction(program_id, admin_key, data))
+}
+
+/// set_validation_policy_instruction create a SetValidationPolicy instruction
+///
+/// The admin pays for the room the policy takes in the RegistryConfig.
+pub fn set_validation_policy_instruction(
+    program_id: &Pubkey,
+    admin_key: &Pubkey,
+    policy: ValidationPolicy,
+) -> Result<Instruction, ProgramError> {
+    msg!("set_validation_policy_instruction");
+    let data = RegistryInstruction::SetValidationPolicy { policy }.pack()?;
+    Ok(resize_config_instruction(program_id, admin_key, data))
+}
+
+/// verify_mint_instruction create a VerifyMint instruction
+///
+/// Extensions in an older layout are migrated, paid by the curator.
+pub fn verify_mint_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    curator_key: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("verify_mint_instruction");
+    let data = RegistryInstruction::VerifyMint.pack()?;
+    Ok(curator_instruction(program_id, mint_key, curator_key, data))
+}
+
+/// unverify_mint_instruction create an UnverifyMint instruction
+pub fn unverify_mint_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    curator_key: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    msg!("unverify_mint_instruction");
+    let data = RegistryInstruction::UnverifyMint.pack()?;
+    Ok(curator_instruction(program_id, mint_key, curator_key, data))
+}
+
+/// Accounts shared by the instructions resizing the RegistryConfig
+fn resize_config_instruction(program_id: &Pubkey, admin_key: &Pubkey, data: Vec<u8>) -> Instruction {
+    let (config_key, _) = find_registry_config_address(program_id);
+
+    let accounts = vec![
+        AccountMeta::new(config_key, false),
+        AccountMeta::new(*admin_key, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Accounts shared by the instructions signed by a curator
+fn curator_instruction(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    curator_key: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    let (config_key, _) = find_registry_config_address(program_id);
+    let (mintext_key, _) = find_mint_extension_address(program_id, mint_key);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(config_key, false),
+        AccountMeta::new_readonly(*mint_key, false),
+        AccountMeta::new(*curator_key, true),
+        AccountMeta::new(mintext_key, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Accounts shared by the instructions signed by the registry admin
+fn admin_instruction(
+    program_id: &Pubkey,
+    admin_key: &Pubkey,
+    data: Vec<u8>,
+    extra_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let (config_key, _) = find_registry_config_address(program_id);
+
+    let mut accounts = Vec::with_capacity(2 + extra_accounts.len());
+    accounts.push(AccountMeta::new(config_key, false));
+    accounts.push(AccountMeta::new_readonly(*admin_key, true));
+    accounts.extend(extra_accounts);
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_instruction_register_mint() {
         let check = RegistryInstruction::RegisterMint{
-            mint: Pubkey::new(&[1u8;32]),
+            mint: Pubkey::new_from_array([1u8;32]),
             symbol: String::from(""),
             name: String::from(""),
         };
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[1]);
         expect.extend_from_slice(&[1u8;32]);
@@ -287,11 +1283,11 @@ mod test {
         assert_eq!(unpacked, check); 
 
         let check = RegistryInstruction::RegisterMint{
-            mint: Pubkey::new(&[2u8;32]),
+            mint: Pubkey::new_from_array([2u8;32]),
             symbol: String::from("CZCOIN"),
             name: String::from("CZ's COIN"),
         };
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[1]);
         expect.extend_from_slice(&[2u8;32]);
@@ -307,11 +1303,11 @@ mod test {
 
 
         let check = RegistryInstruction::RegisterMint{
-            mint: Pubkey::new(&[2u8;32]),
+            mint: Pubkey::new_from_array([2u8;32]),
             symbol: String::from(""),
             name: String::from("CZ's COIN"),
         };
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[1]);
         expect.extend_from_slice(&[2u8;32]);
@@ -326,11 +1322,11 @@ mod test {
         assert_eq!(unpacked, check);
 
         let check = RegistryInstruction::RegisterMint{
-            mint: Pubkey::new(&[2u8;32]),
+            mint: Pubkey::new_from_array([2u8;32]),
             symbol: String::from("CZCOIN"),
             name: String::from(""),
         };
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[1]);
         expect.extend_from_slice(&[2u8;32]);
@@ -351,7 +1347,7 @@ mod test {
             symbol: String::from(""),
             name: String::from(""),
         };
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[3]);
         expect.extend_from_slice(&[0]);
@@ -364,7 +1360,7 @@ mod test {
             symbol: String::from("CZCOIN"),
             name: String::from("CZ's COIN"),
         };
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[3]);
         let symbol = String::from("CZCOIN");
@@ -381,7 +1377,7 @@ mod test {
             symbol: String::from(""),
             name: String::from("CZ's COIN"),
         };
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[3]);
         let symbol = String::from("");
@@ -398,7 +1394,7 @@ mod test {
             symbol: String::from("CZCOIN"),
             name: String::from(""),
         };
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[3]);
         let symbol = String::from("CZCOIN");
@@ -412,14 +1408,570 @@ mod test {
         assert_eq!(unpacked, check); 
     }
 
+    #[test]
+    fn test_instruction_update_mint() {
+        let check = RegistryInstruction::UpdateMint{
+            update: MintUpdate::default(),
+        };
+        assert_eq!(check.pack().unwrap(), vec![21, 0]);
+        assert_eq!(RegistryInstruction::unpack(&[21, 0]).unwrap(), check);
+
+        let check = RegistryInstruction::UpdateMint{
+            update: MintUpdate::default()
+                .name(String::from("CZ's COIN"))
+                .expect_symbol(String::from("CZ")),
+        };
+        let packed = check.pack().unwrap();
+        let mut expect = vec![21, MintUpdate::NAME | MintUpdate::EXPECTED_SYMBOL];
+        expect.extend_from_slice(&[9]);
+        expect.extend_from_slice(b"CZ's COIN");
+        expect.extend_from_slice(&[2]);
+        expect.extend_from_slice(b"CZ");
+        assert_eq!(packed, expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+
+        let check = RegistryInstruction::UpdateMint{
+            update: MintUpdate::default()
+                .symbol(String::from("CZCOIN"))
+                .name(String::from(""))
+                .expect_symbol(String::from("CZ"))
+                .expect_name(String::from("CZ")),
+        };
+        let packed = check.pack().unwrap();
+        assert_eq!(packed[1], 0b1111);
+        assert_eq!(RegistryInstruction::unpack(&packed).unwrap(), check);
+
+        // unknown bits and fields announced by the mask but missing
+        assert_eq!(
+            RegistryInstruction::unpack(&[21, 0b1_0000]),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&[21]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&[21, MintUpdate::SYMBOL]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&[21, MintUpdate::SYMBOL, 0, 0]),
+            Err(RegistryError::TrailingBytes.into())
+        );
+
+        let program_id = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let payer_key = Pubkey::new_unique();
+        let update = MintUpdate::default().name(String::from("CZ's COIN"));
+        let instruction =
+            update_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "CZ", update, &payer_key, &[]).unwrap();
+        // the symbol stays, so both index accounts are the current one
+        let (index_key, _) = find_symbol_index_address(&program_id, &ValidationPolicy::default(), "CZ");
+        assert_eq!(instruction.accounts[6].pubkey, index_key);
+        assert_eq!(instruction.accounts[7].pubkey, index_key);
+        assert!(instruction.accounts[2].is_signer);
+        assert_eq!(instruction.accounts[1], AccountMeta::new_readonly(mint_key, false));
+
+        let update = MintUpdate::default().symbol(String::from("CZCOIN"));
+        let instruction =
+            update_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "CZ", update, &payer_key, &[]).unwrap();
+        let (new_index_key, _) = find_symbol_index_address(&program_id, &ValidationPolicy::default(), "CZCOIN");
+        assert_eq!(instruction.accounts[6].pubkey, index_key);
+        assert_eq!(instruction.accounts[7].pubkey, new_index_key);
+    }
+
     #[test]
     fn test_instruction_close_mint() {
         let check = RegistryInstruction::CloseMint;
-        let packed = check.pack();
+        let packed = check.pack().unwrap();
         let mut expect = Vec::new();
         expect.extend_from_slice(&[2]);
         assert_eq!(packed, expect);
         let unpacked = RegistryInstruction::unpack(&expect).unwrap();
         assert_eq!(unpacked, check); 
+
+        // same order as the other instructions, the refund goes elsewhere
+        let program_id = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let destination_key = Pubkey::new_unique();
+        let multisig_key = Pubkey::new_unique();
+        let signer_key = Pubkey::new_unique();
+        let instruction = close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &destination_key, &multisig_key, &[&signer_key]).unwrap();
+        assert_eq!(instruction.accounts[1], AccountMeta::new_readonly(mint_key, false));
+        assert_eq!(instruction.accounts[2], AccountMeta::new_readonly(multisig_key, false));
+        assert_eq!(instruction.accounts[3].pubkey, find_mint_extension_address(&program_id, &mint_key).0);
+        assert_eq!(instruction.accounts[5], AccountMeta::new(destination_key, false));
+        assert_eq!(instruction.accounts[6], AccountMeta::new_readonly(signer_key, true));
+    }
+
+    #[test]
+    fn test_instruction_migrate_extension() {
+        let check = RegistryInstruction::MigrateExtension;
+        let packed = check.pack().unwrap();
+        assert_eq!(packed, vec![4]);
+        let unpacked = RegistryInstruction::unpack(&packed).unwrap();
+        assert_eq!(unpacked, check);
+        assert_eq!(
+            RegistryInstruction::unpack(&[4, 0]),
+            Err(RegistryError::TrailingBytes.into())
+        );
+    }
+
+    #[test]
+    fn test_instruction_sync_mint() {
+        let check = RegistryInstruction::SyncMint;
+        let packed = check.pack().unwrap();
+        assert_eq!(packed, vec![22]);
+        let unpacked = RegistryInstruction::unpack(&packed).unwrap();
+        assert_eq!(unpacked, check);
+
+        let program_id = Pubkey::new_unique();
+        let instruction = sync_mint_instruction(&program_id, &Pubkey::new_unique(), &Pubkey::new_unique()).unwrap();
+        assert_eq!(instruction.accounts[6].pubkey, sysvar::clock::id());
+    }
+
+    #[test]
+    fn test_instruction_metadata() {
+        let check = RegistryInstruction::SetMetadataField{
+            field: MetadataField::Website,
+            value: String::from("https://example.com"),
+        };
+        let packed = check.pack().unwrap();
+        let mut expect = vec![5, 1, 19];
+        expect.extend_from_slice(b"https://example.com");
+        assert_eq!(packed, expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+
+        let check = RegistryInstruction::ClearMetadataField{
+            field: MetadataField::Description,
+        };
+        assert_eq!(check.pack().unwrap(), vec![6, 2]);
+        assert_eq!(RegistryInstruction::unpack(&[6, 2]).unwrap(), check);
+
+        let check = RegistryInstruction::SetTags{
+            tags: vec![String::from("stablecoin"), String::from("")],
+        };
+        let packed = check.pack().unwrap();
+        let mut expect = vec![7, 2, 10];
+        expect.extend_from_slice(b"stablecoin");
+        expect.push(0);
+        assert_eq!(packed, expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+        assert_eq!(
+            RegistryInstruction::unpack(&[7, 0]).unwrap(),
+            RegistryInstruction::SetTags{tags: vec![]}
+        );
+
+        assert_eq!(
+            RegistryInstruction::unpack(&[6, 3]),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&[6]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&expect[..expect.len() - 1]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+    }
+
+    #[test]
+    fn test_instruction_registry_config() {
+        let check = RegistryInstruction::InitializeRegistry{
+            admin: Pubkey::new_from_array([3u8;32]),
+            registration_fee: 5000,
+        };
+        let packed = check.pack().unwrap();
+        let mut expect = vec![8];
+        expect.extend_from_slice(&[3u8;32]);
+        expect.extend_from_slice(&5000u64.to_le_bytes());
+        assert_eq!(packed, expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+        assert_eq!(
+            RegistryInstruction::unpack(&expect[..expect.len() - 1]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+
+        let check = RegistryInstruction::SetPaused{paused: true};
+        assert_eq!(check.pack().unwrap(), vec![9, 1]);
+        assert_eq!(RegistryInstruction::unpack(&[9, 1]).unwrap(), check);
+        assert_eq!(
+            RegistryInstruction::unpack(&[9, 2]),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+
+        let check = RegistryInstruction::SetRegistrationFee{registration_fee: 7};
+        let mut expect = vec![10];
+        expect.extend_from_slice(&7u64.to_le_bytes());
+        assert_eq!(check.pack().unwrap(), expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+
+        let check = RegistryInstruction::ProposeAdmin{
+            new_admin: Pubkey::new_from_array([4u8;32]),
+        };
+        let mut expect = vec![11];
+        expect.extend_from_slice(&[4u8;32]);
+        assert_eq!(check.pack().unwrap(), expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+
+        assert_eq!(RegistryInstruction::AcceptAdmin.pack().unwrap(), vec![12]);
+        assert_eq!(RegistryInstruction::unpack(&[12]).unwrap(), RegistryInstruction::AcceptAdmin);
+        assert_eq!(RegistryInstruction::WithdrawFees.pack().unwrap(), vec![13]);
+        assert_eq!(RegistryInstruction::unpack(&[13]).unwrap(), RegistryInstruction::WithdrawFees);
+    }
+
+    #[test]
+    fn test_instruction_curators() {
+        let check = RegistryInstruction::AddCurator{
+            curator: Pubkey::new_from_array([5u8;32]),
+        };
+        let mut expect = vec![14];
+        expect.extend_from_slice(&[5u8;32]);
+        assert_eq!(check.pack().unwrap(), expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+
+        let check = RegistryInstruction::RemoveCurator{
+            curator: Pubkey::new_from_array([5u8;32]),
+        };
+        expect[0] = 15;
+        assert_eq!(check.pack().unwrap(), expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+        assert_eq!(
+            RegistryInstruction::unpack(&expect[..32]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+
+        assert_eq!(RegistryInstruction::VerifyMint.pack().unwrap(), vec![16]);
+        assert_eq!(RegistryInstruction::unpack(&[16]).unwrap(), RegistryInstruction::VerifyMint);
+        assert_eq!(RegistryInstruction::UnverifyMint.pack().unwrap(), vec![17]);
+        assert_eq!(RegistryInstruction::unpack(&[17]).unwrap(), RegistryInstruction::UnverifyMint);
+    }
+
+    #[test]
+    fn test_instruction_set_validation_policy() {
+        let policy = ValidationPolicy {
+            reserved_symbols: vec![String::from("USDC")],
+            max_symbol_len: 8,
+            ..ValidationPolicy::default()
+        };
+        let check = RegistryInstruction::SetValidationPolicy{ policy: policy.clone() };
+        let mut expect = vec![23];
+        expect.extend_from_slice(&borsh::to_vec(&policy).unwrap());
+        assert_eq!(check.pack().unwrap(), expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+        assert_eq!(
+            RegistryInstruction::unpack(&expect[..expect.len() - 1]),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+        expect.push(0);
+        assert_eq!(
+            RegistryInstruction::unpack(&expect),
+            Err(RegistryError::TrailingBytes.into())
+        );
+    }
+
+    #[test]
+    fn test_instruction_register_mint_with_proof() {
+        let check = RegistryInstruction::RegisterMintWithProof{
+            mint: Pubkey::new_from_array([7u8;32]),
+            symbol: String::from("SYM"),
+            name: String::from("name"),
+            proof: RegistrationProof::AdminAttestation,
+        };
+        let mut expect = vec![20];
+        expect.extend_from_slice(&[7u8;32]);
+        expect.push(3);
+        expect.extend_from_slice(b"SYM");
+        expect.push(4);
+        expect.extend_from_slice(b"name");
+        expect.push(2);
+        assert_eq!(check.pack().unwrap(), expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+
+        let last = expect.len() - 1;
+        expect[last] = 3;
+        assert_eq!(
+            RegistryInstruction::unpack(&expect),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&expect[..last]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+
+        let instruction = register_mint_with_attestation_instruction(
+            &Pubkey::new_unique(),
+            &ValidationPolicy::default(),
+            &Pubkey::new_from_array([7u8;32]),
+            String::from("SYM"),
+            String::from("name"),
+            &Pubkey::new_from_array([8u8;32]),
+            &Pubkey::new_from_array([9u8;32]),
+        ).unwrap();
+        assert_eq!(instruction.accounts.len(), 8);
+        assert_eq!(instruction.accounts[1], AccountMeta::new_readonly(Pubkey::new_from_array([7u8;32]), false));
+        assert_eq!(instruction.accounts[7].pubkey, Pubkey::new_from_array([9u8;32]));
+        assert!(instruction.accounts[7].is_signer);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_instruction_update_authority() {
+        let check = RegistryInstruction::SetUpdateAuthority{
+            new_authority: Pubkey::new_from_array([6u8;32]),
+        };
+        let mut expect = vec![18];
+        expect.extend_from_slice(&[6u8;32]);
+        assert_eq!(check.pack().unwrap(), expect);
+        assert_eq!(RegistryInstruction::unpack(&expect).unwrap(), check);
+        assert_eq!(
+            RegistryInstruction::unpack(&expect[..32]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+
+        assert_eq!(RegistryInstruction::RevokeUpdateAuthority.pack().unwrap(), vec![19]);
+        assert_eq!(
+            RegistryInstruction::unpack(&[19]).unwrap(),
+            RegistryInstruction::RevokeUpdateAuthority
+        );
+    }
+
+    #[test]
+    fn test_instruction_unpack_malformed() {
+        let check = RegistryInstruction::RegisterMint{
+            mint: Pubkey::new_from_array([2u8;32]),
+            symbol: String::from("CZCOIN"),
+            name: String::from("CZ's COIN"),
+        };
+        let packed = check.pack().unwrap();
+
+        assert_eq!(
+            RegistryInstruction::unpack(&[]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&[23]),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+
+        // cut inside the mint and right before a length prefix
+        assert_eq!(
+            RegistryInstruction::unpack(&packed[..20]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&packed[..33]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+
+        // cut inside the symbol
+        assert_eq!(
+            RegistryInstruction::unpack(&packed[..36]),
+            Err(RegistryError::InvalidStringLength.into())
+        );
+        let mut buf = packed.clone();
+        buf[33] = 200;
+        assert_eq!(
+            RegistryInstruction::unpack(&buf),
+            Err(RegistryError::InvalidStringLength.into())
+        );
+
+        let mut buf = packed.clone();
+        buf[34] = 0xff;
+        assert_eq!(
+            RegistryInstruction::unpack(&buf),
+            Err(RegistryError::InvalidUtf8.into())
+        );
+
+        let mut buf = packed;
+        buf.push(0);
+        assert_eq!(
+            RegistryInstruction::unpack(&buf),
+            Err(RegistryError::TrailingBytes.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&[2, 0]),
+            Err(RegistryError::TrailingBytes.into())
+        );
+        assert_eq!(
+            RegistryInstruction::unpack(&[3, 0]),
+            Err(RegistryError::TruncatedInstruction.into())
+        );
+    }
+
+    #[test]
+    fn test_instruction_pack_long_strings() {
+        let long = "A".repeat(256);
+        let mint = Pubkey::new_unique();
+        for check in [
+            RegistryInstruction::RegisterMint { mint, symbol: long.clone(), name: String::new() },
+            RegistryInstruction::ModifyMint { symbol: String::new(), name: long.clone() },
+            RegistryInstruction::SetMetadataField { field: MetadataField::Website, value: long.clone() },
+            RegistryInstruction::SetTags { tags: vec![long.clone()] },
+            RegistryInstruction::SetTags { tags: vec![String::new(); 256] },
+            RegistryInstruction::RegisterMintWithProof {
+                mint,
+                symbol: String::new(),
+                name: long.clone(),
+                proof: RegistrationProof::MintAuthority,
+            },
+            RegistryInstruction::UpdateMint { update: MintUpdate::default().expect_name(long.clone()) },
+        ] {
+            assert_eq!(check.pack(), Err(RegistryError::InvalidInstruction.into()));
+        }
+
+        // 255 bytes still fit the length byte
+        let check = RegistryInstruction::ModifyMint { symbol: String::new(), name: long[..255].to_string() };
+        assert_eq!(RegistryInstruction::unpack(&check.pack().unwrap()).unwrap(), check);
+
+        // the builders refuse them too, ModifyMint going through UpdateMint
+        let program_id = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        assert_eq!(
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint, "SYM", long, String::new(), &payer, &[]),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+        let instruction =
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint, "SYM", String::from("SYM2"), String::from("name"), &payer, &[])
+                .unwrap();
+        assert_eq!(
+            RegistryInstruction::unpack(&instruction.data).unwrap(),
+            RegistryInstruction::UpdateMint {
+                update: MintUpdate::default().symbol(String::from("SYM2")).name(String::from("name")),
+            }
+        );
+    }
+
+    #[test]
+    fn test_instruction_borsh() {
+        let checks = vec![
+            RegistryInstruction::RegisterMint{
+                mint: Pubkey::new_from_array([2u8;32]),
+                symbol: String::from("CZCOIN"),
+                name: String::from("CZ's COIN"),
+            },
+            RegistryInstruction::CloseMint,
+            RegistryInstruction::ModifyMint{
+                symbol: String::from("CZCOIN"),
+                name: String::from(""),
+            },
+            RegistryInstruction::MigrateExtension,
+            RegistryInstruction::SetMetadataField{
+                field: MetadataField::LogoUri,
+                value: String::from("https://example.com/logo.png"),
+            },
+            RegistryInstruction::ClearMetadataField{
+                field: MetadataField::Website,
+            },
+            RegistryInstruction::SetTags{
+                tags: vec![String::from("wrapped")],
+            },
+            RegistryInstruction::InitializeRegistry{
+                admin: Pubkey::new_from_array([3u8;32]),
+                registration_fee: 5000,
+            },
+            RegistryInstruction::SetPaused{paused: true},
+            RegistryInstruction::SetRegistrationFee{registration_fee: 0},
+            RegistryInstruction::ProposeAdmin{
+                new_admin: Pubkey::new_from_array([4u8;32]),
+            },
+            RegistryInstruction::AcceptAdmin,
+            RegistryInstruction::WithdrawFees,
+            RegistryInstruction::AddCurator{
+                curator: Pubkey::new_from_array([5u8;32]),
+            },
+            RegistryInstruction::RemoveCurator{
+                curator: Pubkey::new_from_array([5u8;32]),
+            },
+            RegistryInstruction::VerifyMint,
+            RegistryInstruction::UnverifyMint,
+            RegistryInstruction::SetUpdateAuthority{
+                new_authority: Pubkey::new_from_array([6u8;32]),
+            },
+            RegistryInstruction::RevokeUpdateAuthority,
+            RegistryInstruction::RegisterMintWithProof{
+                mint: Pubkey::new_from_array([7u8;32]),
+                symbol: String::from("SYM"),
+                name: String::from("name"),
+                proof: RegistrationProof::FreezeAuthority,
+            },
+            RegistryInstruction::UpdateMint{
+                update: MintUpdate::default().name(String::from("CZ's COIN")),
+            },
+            RegistryInstruction::SyncMint,
+            RegistryInstruction::SetValidationPolicy{
+                policy: ValidationPolicy::default(),
+            },
+        ];
+        for (variant, check) in checks.into_iter().enumerate() {
+            let packed = check.pack_borsh();
+            assert_eq!(packed[0], BORSH_INSTRUCTION_VERSION);
+            assert_eq!(packed[1], variant as u8);
+            let (unpacked, encoding) = RegistryInstruction::unpack_versioned(&packed).unwrap();
+            assert_eq!(unpacked, check);
+            assert_eq!(encoding, Encoding::Borsh);
+            let (unpacked, encoding) = RegistryInstruction::unpack_versioned(&check.pack().unwrap()).unwrap();
+            assert_eq!(unpacked, check);
+            assert_eq!(encoding, Encoding::Legacy);
+        }
+
+        let mut expect = vec![BORSH_INSTRUCTION_VERSION, 2];
+        expect.extend_from_slice(&3u32.to_le_bytes());
+        expect.extend_from_slice(b"SYM");
+        expect.extend_from_slice(&4u32.to_le_bytes());
+        expect.extend_from_slice(b"name");
+        assert_eq!(
+            RegistryInstruction::unpack(&expect).unwrap(),
+            RegistryInstruction::ModifyMint{
+                symbol: String::from("SYM"),
+                name: String::from("name"),
+            }
+        );
+
+        assert_eq!(
+            RegistryInstruction::unpack(&expect[..expect.len() - 1]),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+        let mut buf = expect.clone();
+        buf.push(0);
+        assert_eq!(
+            RegistryInstruction::unpack(&buf),
+            Err(RegistryError::TrailingBytes.into())
+        );
+        let mut buf = expect;
+        buf[1] = 22;
+        assert_eq!(
+            RegistryInstruction::unpack(&buf),
+            Err(RegistryError::InvalidInstruction.into())
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_instruction_unpack_random_bytes(
+            data in proptest::collection::vec(any::<u8>(), 0..128),
+        ) {
+            // must never panic; anything that decodes packs back to the input
+            match RegistryInstruction::unpack_versioned(&data) {
+                Ok((instruction, Encoding::Legacy)) => prop_assert_eq!(instruction.pack().unwrap(), data),
+                Ok((instruction, Encoding::Borsh)) => prop_assert_eq!(instruction.pack_borsh(), data),
+                Err(_) => {}
+            }
+        }
+
+        #[test]
+        fn test_instruction_unpack_corrupted(
+            symbol in "\\PC{0,32}",
+            name in "\\PC{0,32}",
+            index in any::<prop::sample::Index>(),
+            byte in any::<u8>(),
+        ) {
+            let mut data = RegistryInstruction::ModifyMint { symbol, name }.pack().unwrap();
+            let index = index.index(data.len());
+            data[index] = byte;
+            if let Ok(instruction) = RegistryInstruction::unpack(&data) {
+                prop_assert_eq!(instruction.pack().unwrap(), data);
+            }
+        }
+    }
+}
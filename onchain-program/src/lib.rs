@@ -4,9 +4,11 @@
 //! a extension for mint symbol, support symbol/name etc for mint with a list file
 
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod processor;
 pub mod state;
+pub mod validation;
 
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
@@ -14,4 +16,65 @@ pub mod entrypoint;
 // Export current sdk types for downstream users building with a different sdk version
 pub use solana_program;
 
+use solana_program::{hash::hash, pubkey::Pubkey};
 
+/// The SPL Token program, owner of the mints that can be registered
+pub mod spl_token {
+    solana_program::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+}
+
+/// The SPL Token-2022 program, accepted with the `token-2022` feature
+pub mod spl_token_2022 {
+    solana_program::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
+/// Wire format of instruction data.
+///
+/// The Borsh format starts with a version byte, so both formats can be told
+/// apart before decoding. Clients in other languages can be generated from
+/// the Borsh schema of [RegistryInstruction](instruction/enum.RegistryInstruction.html)
+/// and [MintExtension](state/struct.MintExtension.html), whose accounts are
+/// tagged with an [AccountType](state/enum.AccountType.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    /// Hand packed layout
+    Legacy,
+    /// Version byte followed by the Borsh encoding
+    Borsh,
+}
+
+/// Seed prefix of the MintExtension address derived from a mint
+pub const MINT_EXTENSION_SEED: &[u8] = b"mint_extension";
+
+/// Derives the MintExtension address of a mint and its bump seed.
+///
+/// Every mint has exactly one MintExtension, so wallets can fetch a token's
+/// symbol and name with a single account lookup.
+pub fn find_mint_extension_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINT_EXTENSION_SEED, mint.as_ref()], program_id)
+}
+
+/// Seed prefix of the SymbolIndex address derived from a symbol
+pub const SYMBOL_INDEX_SEED: &[u8] = b"symbol_index";
+
+/// Derives the SymbolIndex address of a symbol and its bump seed.
+///
+/// The symbol is normalised under the policy of the registry with
+/// [normalize_symbol](validation/fn.normalize_symbol.html) and hashed, so
+/// symbols differing only in case share one index and any symbol fits a seed.
+pub fn find_symbol_index_address(
+    program_id: &Pubkey,
+    policy: &validation::ValidationPolicy,
+    symbol: &str,
+) -> (Pubkey, u8) {
+    let symbol_hash = hash(validation::normalize_symbol(policy, symbol).as_bytes());
+    Pubkey::find_program_address(&[SYMBOL_INDEX_SEED, symbol_hash.as_ref()], program_id)
+}
+
+/// Seed of the RegistryConfig address
+pub const REGISTRY_CONFIG_SEED: &[u8] = b"registry_config";
+
+/// Derives the address of the program's single RegistryConfig and its bump seed.
+pub fn find_registry_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY_CONFIG_SEED], program_id)
+}
@@ -2,21 +2,38 @@
 
 use crate::{
     error::RegistryError,
-    instruction::{RegistryInstruction},
-    state::{MintExtension, Mint},
+    event::RegistryEvent,
+    find_mint_extension_address,
+    find_registry_config_address,
+    find_symbol_index_address,
+    instruction::{MetadataField, MintUpdate, RegistryInstruction},
+    spl_token,
+    state::{
+        Mint, MintExtension, MintExtensionLayout, MintExtensionView, MintMetadata, Multisig,
+        RegistrationProof, RegistryConfig, SymbolIndex,
+        MAX_CURATORS, MAX_SIGNERS, MAX_DESCRIPTION_LEN,
+        MAX_LOGO_URI_LEN, MAX_SYMBOL_NAME_LEN, MAX_TAGS, MAX_TAG_LEN, MAX_WEBSITE_LEN,
+    },
+    validation::{normalize_symbol, ValidationPolicy},
+    MINT_EXTENSION_SEED, REGISTRY_CONFIG_SEED, SYMBOL_INDEX_SEED,
 };
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     //decode_error::DecodeError,
     program_error::ProgramError,
     entrypoint::ProgramResult,
-    info,
+    hash::hash,
+    msg,
+    program::{invoke, invoke_signed},
     program_option::COption,
     //program_pack::{IsInitialized, Pack},
     program_pack::{Pack},
+    program_utils::limited_deserialize,
     pubkey::Pubkey,
-    //sysvar::{rent::Rent, Sysvar},
-    //sysvar::{rent::Rent},
+    system_instruction,
+    system_program,
+    sysvar::{clock::Clock, rent::Rent, Sysvar},
 };
 
 /// Program state handler.
@@ -24,376 +41,3863 @@ pub struct Processor {}
 impl Processor {
 
     /// Processes an [Instruction](enum.Instruction.html).
-    pub fn process(_program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
-        info!("mint_registry:process program:");
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        msg!("mint_registry:process program:");
         let instruction = RegistryInstruction::unpack(input)?;
 
+        let (config_info, accounts) = accounts
+            .split_first()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if let RegistryInstruction::InitializeRegistry { admin, registration_fee } = instruction {
+            msg!("mint-registry: Instruction: InitializeRegistry");
+            return Self::process_initialize_registry(
+                program_id,
+                config_info,
+                accounts,
+                admin,
+                registration_fee,
+            );
+        }
+        let config = Self::unpack_registry_config(program_id, config_info)?;
+        let admin_instruction = matches!(
+            instruction,
+            RegistryInstruction::SetPaused { .. }
+                | RegistryInstruction::SetRegistrationFee { .. }
+                | RegistryInstruction::ProposeAdmin { .. }
+                | RegistryInstruction::AcceptAdmin
+                | RegistryInstruction::WithdrawFees
+                | RegistryInstruction::AddCurator { .. }
+                | RegistryInstruction::RemoveCurator { .. }
+                | RegistryInstruction::SetValidationPolicy { .. }
+        );
+        if config.paused && !admin_instruction {
+            return Err(RegistryError::RegistryPaused.into());
+        }
+
         match instruction {
             RegistryInstruction::RegisterMint {
-                mint_authority,
-                freeze_authority,
-                supply,
-                decimals,
                 mint,
                 symbol,
                 name,
             } => {
-                info!("mint-registry: Instruction: RegisterMint");
-                Self::process_register_mint(accounts, mint, symbol, name, mint_authority, freeze_authority, supply, decimals)
+                msg!("mint-registry: Instruction: RegisterMint");
+                Self::process_register_mint(
+                    program_id,
+                    &config,
+                    config_info,
+                    accounts,
+                    mint,
+                    symbol,
+                    name,
+                    RegistrationProof::MintAuthority,
+                )
+            }
+            RegistryInstruction::RegisterMintWithProof {
+                mint,
+                symbol,
+                name,
+                proof,
+            } => {
+                msg!("mint-registry: Instruction: RegisterMintWithProof");
+                Self::process_register_mint(program_id, &config, config_info, accounts, mint, symbol, name, proof)
             }
             RegistryInstruction::CloseMint=>{
-                info!("mint-registry: Instruction: CloseMint");
-                Self::process_close_mint(accounts)
+                msg!("mint-registry: Instruction: CloseMint");
+                Self::process_close_mint(program_id, &config, accounts)
             }
             RegistryInstruction::ModifyMint {
                 symbol,
                 name,
             } => {
-                info!("mint-registry: Instruction: ModifyMint");
-                Self::process_modify_mint(accounts, symbol, name)
+                msg!("mint-registry: Instruction: ModifyMint");
+                Self::process_modify_mint(program_id, &config, accounts, MintUpdate::default().symbol(symbol).name(name))
+            }
+            RegistryInstruction::UpdateMint { update } => {
+                msg!("mint-registry: Instruction: UpdateMint");
+                Self::process_modify_mint(program_id, &config, accounts, update)
+            }
+            RegistryInstruction::SyncMint => {
+                msg!("mint-registry: Instruction: SyncMint");
+                Self::process_sync_mint(program_id, accounts)
+            }
+            RegistryInstruction::MigrateExtension => {
+                msg!("mint-registry: Instruction: MigrateExtension");
+                Self::process_migrate_extension(program_id, accounts)
+            }
+            RegistryInstruction::SetMetadataField { field, value } => {
+                msg!("mint-registry: Instruction: SetMetadataField");
+                Self::process_update_extension(program_id, accounts, |_, metadata| {
+                    *metadata_field(metadata, field) = value;
+                    Ok(())
+                })
+            }
+            RegistryInstruction::ClearMetadataField { field } => {
+                msg!("mint-registry: Instruction: ClearMetadataField");
+                Self::process_update_extension(program_id, accounts, |_, metadata| {
+                    metadata_field(metadata, field).clear();
+                    Ok(())
+                })
+            }
+            RegistryInstruction::SetTags { tags } => {
+                msg!("mint-registry: Instruction: SetTags");
+                Self::process_update_extension(program_id, accounts, |_, metadata| {
+                    metadata.tags = tags;
+                    Ok(())
+                })
+            }
+            RegistryInstruction::SetUpdateAuthority { new_authority } => {
+                msg!("mint-registry: Instruction: SetUpdateAuthority");
+                if new_authority == Pubkey::default() {
+                    return Err(RegistryError::InvalidInstruction.into());
+                }
+                Self::process_update_extension(program_id, accounts, |mint_ext, _| {
+                    mint_ext.set_update_authority(&new_authority)
+                })
+            }
+            RegistryInstruction::RevokeUpdateAuthority => {
+                msg!("mint-registry: Instruction: RevokeUpdateAuthority");
+                Self::process_update_extension(program_id, accounts, |mint_ext, _| {
+                    mint_ext.set_update_authority(&Pubkey::default())
+                })
             }
+            // handled before the config is read
+            RegistryInstruction::InitializeRegistry { .. } => Err(RegistryError::InvalidInstruction.into()),
+            RegistryInstruction::SetPaused { paused } => {
+                msg!("mint-registry: Instruction: SetPaused");
+                Self::process_update_config(config, config_info, accounts, |config| {
+                    config.paused = paused;
+                })
+            }
+            RegistryInstruction::SetRegistrationFee { registration_fee } => {
+                msg!("mint-registry: Instruction: SetRegistrationFee");
+                Self::process_update_config(config, config_info, accounts, |config| {
+                    config.registration_fee = registration_fee;
+                })
+            }
+            RegistryInstruction::ProposeAdmin { new_admin } => {
+                msg!("mint-registry: Instruction: ProposeAdmin");
+                Self::process_update_config(config, config_info, accounts, |config| {
+                    config.pending_admin = new_admin;
+                })
+            }
+            RegistryInstruction::AcceptAdmin => {
+                msg!("mint-registry: Instruction: AcceptAdmin");
+                Self::process_accept_admin(config, config_info, accounts)
+            }
+            RegistryInstruction::WithdrawFees => {
+                msg!("mint-registry: Instruction: WithdrawFees");
+                Self::process_withdraw_fees(config, config_info, accounts)
+            }
+            RegistryInstruction::AddCurator { curator } => {
+                msg!("mint-registry: Instruction: AddCurator");
+                Self::process_resize_config(config, config_info, accounts, |config| {
+                    if !config.curators.contains(&curator) {
+                        config.curators.push(curator);
+                    }
+                    Ok(())
+                })
+            }
+            RegistryInstruction::RemoveCurator { curator } => {
+                msg!("mint-registry: Instruction: RemoveCurator");
+                Self::process_resize_config(config, config_info, accounts, |config| {
+                    let position = config
+                        .curators
+                        .iter()
+                        .position(|key| *key == curator)
+                        .ok_or(RegistryError::NotCurator)?;
+                    config.curators.remove(position);
+                    Ok(())
+                })
+            }
+            RegistryInstruction::SetValidationPolicy { policy } => {
+                msg!("mint-registry: Instruction: SetValidationPolicy");
+                policy.check_limits()?;
+                Self::process_resize_config(config, config_info, accounts, |config| {
+                    config.policy = policy;
+                    Ok(())
+                })
+            }
+            RegistryInstruction::VerifyMint => {
+                msg!("mint-registry: Instruction: VerifyMint");
+                Self::process_set_verified(program_id, &config, accounts, true)
+            }
+            RegistryInstruction::UnverifyMint => {
+                msg!("mint-registry: Instruction: UnverifyMint");
+                Self::process_set_verified(program_id, &config, accounts, false)
+            }
+        }
+    }
+
+    /// Processes an [InitializeRegistry](enum.RegistryInstruction.html) instruction.
+    ///
+    /// Only the upgrade authority recorded in the ProgramData account of the
+    /// program may create the RegistryConfig.
+    fn process_initialize_registry<'a>(
+        program_id: &Pubkey,
+        config_info: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        admin: Pubkey,
+        registration_fee: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let upgrade_authority_info = next_account_info(account_info_iter)?;
+        let program_data_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if !upgrade_authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let (program_data_key, _) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+        if program_data_key != *program_data_info.key
+            || *program_data_info.owner != bpf_loader_upgradeable::id()
+        {
+            return Err(RegistryError::NotUpgradeAuthority.into());
+        }
+        let program_data = program_data_info.data.borrow();
+        let header_len = UpgradeableLoaderState::size_of_programdata_metadata().min(program_data.len());
+        match limited_deserialize(&program_data[..header_len], header_len as u64) {
+            Ok(UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address: Some(upgrade_authority),
+                ..
+            }) if upgrade_authority == *upgrade_authority_info.key => {}
+            _ => return Err(RegistryError::NotUpgradeAuthority.into()),
+        }
+
+        let (config_key, bump_seed) = find_registry_config_address(program_id);
+        if config_key != *config_info.key {
+            return Err(RegistryError::InvalidRegistryConfigAddress.into());
+        }
+        if *config_info.owner != system_program::id() {
+            return Err(RegistryError::RegistryAlreadyInitialized.into());
+        }
+        create_pda_account(
+            upgrade_authority_info,
+            rent,
+            RegistryConfig::default().account_len(),
+            program_id,
+            system_program_info,
+            config_info,
+            &[REGISTRY_CONFIG_SEED, &[bump_seed]],
+        )?;
+
+        let config = RegistryConfig {
+            admin,
+            pending_admin: Pubkey::default(),
+            paused: false,
+            registration_fee,
+            curators: Vec::new(),
+            policy: ValidationPolicy::default(),
+        };
+        config.pack_account(&mut config_info.data.borrow_mut())
+    }
+
+    /// Processes the instructions changing the config on behalf of the admin:
+    /// [SetPaused, SetRegistrationFee and ProposeAdmin](enum.RegistryInstruction.html).
+    fn process_update_config<F: FnOnce(&mut RegistryConfig)>(
+        mut config: RegistryConfig,
+        config_info: &AccountInfo,
+        accounts: &[AccountInfo],
+        update: F,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin_info = next_account_info(account_info_iter)?;
+        Self::check_admin(&config, admin_info)?;
+
+        update(&mut config);
+        config.pack_account(&mut config_info.data.borrow_mut())
+    }
+
+    /// Processes the instructions changing the size of the config:
+    /// [AddCurator, RemoveCurator and SetValidationPolicy](enum.RegistryInstruction.html).
+    ///
+    /// The config account is resized to the new value, paid or refunded to the admin.
+    fn process_resize_config<'a, F: FnOnce(&mut RegistryConfig) -> ProgramResult>(
+        mut config: RegistryConfig,
+        config_info: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        update: F,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        Self::check_admin(&config, admin_info)?;
+
+        update(&mut config)?;
+        if config.curators.len() > MAX_CURATORS {
+            return Err(RegistryError::TooManyCurators.into());
+        }
+
+        resize_account(admin_info, rent, config.account_len(), system_program_info, config_info)?;
+        config.pack_account(&mut config_info.data.borrow_mut())
+    }
+
+    /// Processes the instructions signed by a curator:
+    /// [VerifyMint and UnverifyMint](enum.RegistryInstruction.html).
+    ///
+    /// Accounts in an older layout are migrated on the way.
+    fn process_set_verified(
+        program_id: &Pubkey,
+        config: &RegistryConfig,
+        accounts: &[AccountInfo],
+        verified: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_account_info = next_account_info(account_info_iter)?;
+        let curator_info = next_account_info(account_info_iter)?;
+        let mint_ext_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if !curator_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if !config.is_curator(curator_info.key) {
+            return Err(RegistryError::NotCurator.into());
+        }
+
+        Self::check_mint_extension_address(program_id, mint_account_info, mint_ext_info)?;
+        Self::check_mint_extension_owner(program_id, mint_ext_info)?;
+        Self::check_registered(mint_account_info, &MintExtensionView::new(&mint_ext_info.data.borrow()[..])?)?;
+
+        migrate_to_latest(curator_info, rent, system_program_info, mint_ext_info)?;
+        let mut data = mint_ext_info.data.borrow_mut();
+        let mut mint_ext = MintExtensionView::new(&mut data[..])?;
+        let old_verified = mint_ext.verified();
+        mint_ext.set_verified(verified)?;
+        RegistryEvent::Verified {
+            mint: *mint_account_info.key,
+            old_verified,
+            new_verified: verified,
+            signer: *curator_info.key,
+        }.emit();
+        Ok(())
+    }
+
+    /// Processes an [AcceptAdmin](enum.RegistryInstruction.html) instruction.
+    fn process_accept_admin(
+        mut config: RegistryConfig,
+        config_info: &AccountInfo,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let pending_admin_info = next_account_info(account_info_iter)?;
+        if !pending_admin_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if config.pending_admin == Pubkey::default() || config.pending_admin != *pending_admin_info.key {
+            return Err(RegistryError::NotPendingAdmin.into());
         }
+
+        config.admin = config.pending_admin;
+        config.pending_admin = Pubkey::default();
+        config.pack_account(&mut config_info.data.borrow_mut())
     }
 
-    /// Processes an [RegisterMint](enum.RegistryInstruction.html) instruction.
-    fn process_register_mint(
+    /// Processes a [WithdrawFees](enum.RegistryInstruction.html) instruction.
+    fn process_withdraw_fees(
+        config: RegistryConfig,
+        config_info: &AccountInfo,
         accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin_info = next_account_info(account_info_iter)?;
+        let dest_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        Self::check_admin(&config, admin_info)?;
+
+        let fees = config_info
+            .lamports()
+            .saturating_sub(rent.minimum_balance(config_info.data_len()));
+        **config_info.lamports.borrow_mut() -= fees;
+        **dest_account_info.lamports.borrow_mut() = dest_account_info
+            .lamports()
+            .checked_add(fees)
+            .ok_or(RegistryError::Overflow)?;
+        Ok(())
+    }
+
+    /// Processes an [RegisterMint or RegisterMintWithProof](enum.RegistryInstruction.html)
+    /// instruction.
+    #[allow(clippy::too_many_arguments)]
+    fn process_register_mint<'a>(
+        program_id: &Pubkey,
+        config: &RegistryConfig,
+        config_info: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
         mint: Pubkey,
         symbol: String,
         name: String,
-        mint_authority: Pubkey,
-        freeze_authority: Pubkey,
-        supply: u64,
-        decimals: u8,
+        proof: RegistrationProof,
     ) -> ProgramResult {
-        if symbol.len()>=16 || name.len()>=16 {
-            return Err(RegistryError::SymbolToLong.into());
-        }
+        let (symbol, name) = Self::validate_symbol_name(&config.policy, &symbol, &name)?;
         let account_info_iter = &mut accounts.iter();
         let mint_account_info = next_account_info(account_info_iter)?;
-        let mint_account = Mint::unpack_unchecked(&mint_account_info.data.borrow())?;
+        let mint_account = Self::unpack_mint(mint_account_info)?;
+        if mint != *mint_account_info.key {
+            return Err(RegistryError::MintMismatch.into());
+        }
 
         let mint_owner_info = next_account_info(account_info_iter)?;
         let mint_ext_info= next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        let symbol_index_info = next_account_info(account_info_iter)?;
 
         // check permission
-        if !mint_owner_info.is_signer || !mint_ext_info.is_signer{
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        match mint_account.mint_authority {
-            COption::Some(mint_authority) => {
-                if mint_authority != *mint_owner_info.key {
-                    return Err(RegistryError::NoAuthority.into());
-                } 
+        let payer_info = match proof {
+            RegistrationProof::MintAuthority => match mint_account.mint_authority {
+                COption::Some(mint_authority) => {
+                    Self::validate_authority(mint_account_info.owner, &mint_authority, mint_owner_info, account_info_iter.as_slice())?
+                },
+                COption::None => return Err(RegistryError::NoMintAuthority.into()),
             },
-            COption::None => return Err(RegistryError::NoMintAuthority.into()),
-        }
-        
+            RegistrationProof::FreezeAuthority => {
+                if mint_account.mint_authority.is_some() {
+                    return Err(RegistryError::SupplyNotFixed.into());
+                }
+                match mint_account.freeze_authority {
+                    COption::Some(freeze_authority) => {
+                        Self::validate_authority(mint_account_info.owner, &freeze_authority, mint_owner_info, account_info_iter.as_slice())?
+                    },
+                    COption::None => return Err(RegistryError::NoFreezeAuthority.into()),
+                }
+            },
+            RegistrationProof::AdminAttestation => {
+                if !mint_owner_info.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                Self::check_admin(config, next_account_info(account_info_iter)?)?;
+                mint_owner_info
+            },
+        };
 
-        let mut mint_ext = MintExtension::unpack_unchecked(&mint_ext_info.data.borrow())?;
-        if mint_ext.is_initialized {
-            return Err(RegistryError::AlreadRegistry.into());
+        let bump_seed = Self::check_mint_extension_address(program_id, mint_account_info, mint_ext_info)?;
+        let created = *mint_ext_info.owner == system_program::id();
+        if created {
+            let mint_ext_signer_seeds: &[&[_]] = &[
+                MINT_EXTENSION_SEED,
+                mint_account_info.key.as_ref(),
+                &[bump_seed],
+            ];
+            create_pda_account(
+                payer_info,
+                rent,
+                MintExtension::account_len(MintExtensionLayout::LATEST),
+                program_id,
+                system_program_info,
+                mint_ext_info,
+                mint_ext_signer_seeds,
+            )?;
         }
-        mint_ext.mint_authority = mint_authority;
-        mint_ext.freeze_authority = freeze_authority;
-        mint_ext.supply = supply;
-        mint_ext.decimals = decimals;
-        mint_ext.is_initialized = true;
-        mint_ext.mint = mint;
-        mint_ext.symbol_len = symbol.len() as u8;
-        for  i in 0..symbol.len() {
-            mint_ext.symbol[i] = symbol.as_bytes()[i];
+        Self::check_mint_extension_owner(program_id, mint_ext_info)?;
+        if !created {
+            if MintExtensionView::new(&mint_ext_info.data.borrow()[..])?.is_initialized() {
+                return Err(RegistryError::AlreadRegistry.into());
+            }
+            // extensions closed by earlier versions of the program stay
+            // allocated: move to the latest layout and drop their metadata
+            let len = MintExtension::account_len(MintExtensionLayout::LATEST);
+            resize_account(payer_info, rent, len, system_program_info, mint_ext_info)?;
+        }
+        // an account below the minimum balance would be collected with the
+        // registration
+        if !rent.is_exempt(mint_ext_info.lamports(), mint_ext_info.data_len()) {
+            return Err(RegistryError::NotRentExempt.into());
         }
-        mint_ext.name_len = name.len() as u8;
-        for  i in 0..name.len() {
-            mint_ext.name[i] = name.as_bytes()[i];
+
+        claim_symbol(
+            program_id,
+            &config.policy,
+            &symbol,
+            mint_account_info.key,
+            payer_info,
+            rent,
+            system_program_info,
+            symbol_index_info,
+        )?;
+        if config.registration_fee > 0 {
+            invoke(
+                &system_instruction::transfer(payer_info.key, config_info.key, config.registration_fee),
+                &[payer_info.clone(), config_info.clone(), system_program_info.clone()],
+            )?;
         }
 
-        MintExtension::pack(mint_ext, &mut mint_ext_info.data.borrow_mut())?;
+        let mut data = mint_ext_info.data.borrow_mut();
+        let mut mint_ext = MintExtensionView::init(&mut data[..])?;
+        mint_ext.set_mint_authority(&mint_account.mint_authority.unwrap_or_default());
+        mint_ext.set_freeze_authority(&mint_account.freeze_authority.unwrap_or_default());
+        mint_ext.set_supply(mint_account.supply);
+        mint_ext.set_decimals(mint_account.decimals);
+        mint_ext.set_is_initialized(true);
+        mint_ext.set_mint(&mint);
+        mint_ext.set_symbol(&symbol)?;
+        mint_ext.set_name(&name)?;
+        mint_ext.set_update_authority(mint_owner_info.key)?;
+        mint_ext.set_registration_proof(proof)?;
 
+        RegistryEvent::Registered {
+            mint,
+            symbol,
+            name,
+            proof,
+            signer: *mint_owner_info.key,
+        }.emit();
         Ok(())
     }
 
     /// Processes a [CloseMint](enum.RegistryInstruction.html) instruction.
-    pub fn process_close_mint(accounts: &[AccountInfo]) -> ProgramResult {
+    ///
+    /// Legacy extensions at keypair addresses are closed without releasing a
+    /// symbol index, as they predate the index.
+    pub fn process_close_mint(program_id: &Pubkey, config: &RegistryConfig, accounts: &[AccountInfo]) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
+        let mint_account_info = next_account_info(account_info_iter)?;
+        Self::unpack_mint(mint_account_info)?;
+        let mint_owner_info = next_account_info(account_info_iter)?;
         let source_account_info = next_account_info(account_info_iter)?;
+        let symbol_index_info = next_account_info(account_info_iter)?;
         let dest_account_info = next_account_info(account_info_iter)?;
-        let mint_account_info= next_account_info(account_info_iter)?;
-        let mint_account = Mint::unpack_unchecked(&mint_account_info.data.borrow())?;
-        let mut source_account = MintExtension::unpack_unchecked(&source_account_info.data.borrow())?;
-
-        //check permission
-        if !dest_account_info.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        match mint_account.mint_authority {
-            COption::Some(mint_authority) => {
-                if mint_authority != *dest_account_info.key {
-                    return Err(RegistryError::NoAuthority.into());
-                } 
-            },
-            COption::None => return Err(RegistryError::NoMintAuthority.into()),
+        if dest_account_info.key == source_account_info.key || dest_account_info.key == symbol_index_info.key {
+            return Err(ProgramError::InvalidAccountData);
         }
 
-        let dest_starting_lamports = dest_account_info.lamports();
-        **dest_account_info.lamports.borrow_mut() = dest_starting_lamports
-            .checked_add(source_account_info.lamports())
-            .ok_or(RegistryError::Overflow)?;
+        let legacy = Self::check_legacy_mint_extension_address(program_id, mint_account_info, source_account_info)?;
+        Self::check_mint_extension_owner(program_id, source_account_info)?;
+        let event = {
+            let data = source_account_info.data.borrow();
+            let source_account = MintExtensionView::new(&data[..])?;
+            // the extension must belong to the mint the authority was checked against
+            Self::check_registered(mint_account_info, &source_account)?;
+            Self::check_update_authority(mint_account_info.owner, &source_account.update_authority(), mint_owner_info, account_info_iter.as_slice())?;
 
-        **source_account_info.lamports.borrow_mut() = 0;
-        source_account.is_initialized = false;
-        MintExtension::pack(source_account, &mut source_account_info.data.borrow_mut())?;
+            // symbols that are not valid UTF-8 predate the index, as do
+            // legacy accounts, whose symbol may be held by the current
+            // extension of the mint
+            if let (false, Ok(symbol)) = (legacy, source_account.symbol()) {
+                release_symbol(
+                    program_id,
+                    &config.policy,
+                    symbol,
+                    mint_account_info.key,
+                    dest_account_info,
+                    symbol_index_info,
+                )?;
+            }
+            RegistryEvent::Closed {
+                mint: *mint_account_info.key,
+                old_symbol: String::from(source_account.symbol().unwrap_or_default()),
+                old_name: String::from(source_account.name().unwrap_or_default()),
+                signer: *mint_owner_info.key,
+            }
+        };
 
+        // hand the account back to the system program, so that nothing of the
+        // registration survives even if it is funded again in this transaction
+        close_account(source_account_info, dest_account_info)?;
+        event.emit();
         Ok(())
     }
 
-    /// Processes an [ModifyMint](enum.RegistryInstruction.html) instruction.
+    /// Processes a [ModifyMint or UpdateMint](enum.RegistryInstruction.html)
+    /// instruction, changing the fields set in the update.
+    ///
+    /// Accounts in an older layout are migrated on the way, as their fields
+    /// are too short for the longer symbols and names.
     fn process_modify_mint(
+        program_id: &Pubkey,
+        config: &RegistryConfig,
         accounts: &[AccountInfo],
-        symbol: String,
-        name: String,
+        update: MintUpdate,
     ) -> ProgramResult {
-        if symbol.len()>=16 || name.len()>=16 {
-            return Err(RegistryError::SymbolToLong.into());
+        if update.symbol.is_none() && update.name.is_none() {
+            // nothing to write, so nothing to migrate, charge or log
+            return Err(RegistryError::InvalidInstruction.into());
         }
+        let policy = &config.policy;
+        let symbol = update.symbol.as_deref().map(|symbol| Self::validate_symbol(policy, symbol)).transpose()?;
+        let name = update.name.as_deref().map(|name| Self::validate_name(policy, name)).transpose()?;
         let account_info_iter = &mut accounts.iter();
         let mint_account_info = next_account_info(account_info_iter)?;
-        let mint_account = Mint::unpack_unchecked(&mint_account_info.data.borrow())?;
+        Self::unpack_mint(mint_account_info)?;
         let mint_owner_info = next_account_info(account_info_iter)?;
         let mint_ext_info= next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        // both indexes come before the multisig signers, so they are passed
+        // even when the update leaves the symbol alone
+        let old_symbol_index_info = next_account_info(account_info_iter)?;
+        let symbol_index_info = next_account_info(account_info_iter)?;
 
-        // check permission
-        if !mint_owner_info.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        match mint_account.mint_authority {
-            COption::Some(mint_authority) => {
-                if mint_authority != *mint_owner_info.key {
-                    return Err(RegistryError::NoAuthority.into());
-                } 
-            },
-            COption::None => return Err(RegistryError::NoMintAuthority.into()),
+        Self::check_mint_extension_address(program_id, mint_account_info, mint_ext_info)?;
+        Self::check_mint_extension_owner(program_id, mint_ext_info)?;
+        let (payer_info, old_symbol, old_name) = {
+            let data = mint_ext_info.data.borrow();
+            let mint_ext = MintExtensionView::new(&data[..])?;
+            Self::check_registered(mint_account_info, &mint_ext)?;
+            let payer_info =
+                Self::check_update_authority(mint_account_info.owner, &mint_ext.update_authority(), mint_owner_info, account_info_iter.as_slice())?;
+
+            if let Some(expected) = &update.expected_symbol {
+                if mint_ext.symbol().ok() != Some(expected.as_str()) {
+                    return Err(RegistryError::ValueMismatch.into());
+                }
+            }
+            if let Some(expected) = &update.expected_name {
+                if mint_ext.name().ok() != Some(expected.as_str()) {
+                    return Err(RegistryError::ValueMismatch.into());
+                }
+            }
+
+            // release the old symbol before claiming the new one, unless both
+            // normalise to the same index; the keys passed in cannot tell, as
+            // the same account may be passed twice
+            if let (Some(new_symbol), Ok(old_symbol)) = (&symbol, mint_ext.symbol()) {
+                let (old_symbol_index_key, _) = find_symbol_index_address(program_id, policy, old_symbol);
+                let (symbol_index_key, _) = find_symbol_index_address(program_id, policy, new_symbol);
+                if old_symbol_index_key != symbol_index_key {
+                    release_symbol(
+                        program_id,
+                        policy,
+                        old_symbol,
+                        mint_account_info.key,
+                        payer_info,
+                        old_symbol_index_info,
+                    )?;
+                }
+            }
+            (
+                payer_info,
+                String::from(mint_ext.symbol().unwrap_or_default()),
+                String::from(mint_ext.name().unwrap_or_default()),
+            )
+        };
+        migrate_to_latest(payer_info, rent, system_program_info, mint_ext_info)?;
+        if let Some(symbol) = &symbol {
+            claim_symbol(
+                program_id,
+                policy,
+                symbol,
+                mint_account_info.key,
+                payer_info,
+                rent,
+                system_program_info,
+                symbol_index_info,
+            )?;
         }
-        
 
-        let mut mint_ext = MintExtension::unpack_unchecked(&mint_ext_info.data.borrow())?;
-        if !mint_ext.is_initialized {
-            return Err(RegistryError::NoRegistry.into()); 
+        let mut data = mint_ext_info.data.borrow_mut();
+        let mut mint_ext = MintExtensionView::new(&mut data[..])?;
+        if let Some(symbol) = &symbol {
+            mint_ext.set_symbol(symbol)?;
         }
-        mint_ext.symbol_len = symbol.len() as u8;
-        for  i in 0..symbol.len() {
-            mint_ext.symbol[i] = symbol.as_bytes()[i];
+        if let Some(name) = &name {
+            mint_ext.set_name(name)?;
         }
-        mint_ext.name_len = name.len() as u8;
-        for  i in 0..name.len() {
-            mint_ext.name[i] = name.as_bytes()[i];
+        if symbol.is_some() || name.is_some() {
+            // curators vouched for the old symbol and name
+            mint_ext.set_verified(false)?;
         }
 
-        MintExtension::pack(mint_ext, &mut mint_ext_info.data.borrow_mut())?;
-
+        RegistryEvent::Modified {
+            mint: *mint_account_info.key,
+            new_symbol: symbol.unwrap_or_else(|| old_symbol.clone()),
+            old_symbol,
+            new_name: name.unwrap_or_else(|| old_name.clone()),
+            old_name,
+            signer: *mint_owner_info.key,
+        }.emit();
         Ok(())
     }
- 
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    use crate::instruction::*;
-    //use solana_program::{clock::Epoch, instruction::Instruction, sysvar::rent};
-    use solana_program::{instruction::Instruction, sysvar::{rent::Rent}};
-    use solana_sdk::account::{
-        //create_account, create_is_signer_account_infos, Account,
-        create_is_signer_account_infos, Account,
-    };
+    /// Processes a [MigrateExtension](enum.RegistryInstruction.html) instruction.
+    fn process_migrate_extension(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_account_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let mint_ext_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
 
-    fn do_process_instruction(
-        instruction: Instruction,
-        accounts: Vec<&mut Account>,
-    ) -> ProgramResult {
-        let mut meta = instruction
-            .accounts
-            .iter()
-            .zip(accounts)
-            .map(|(account_meta, account)| (&account_meta.pubkey, account_meta.is_signer, account))
-            .collect::<Vec<_>>();
+        Self::check_legacy_mint_extension_address(program_id, mint_account_info, mint_ext_info)?;
+        Self::check_mint_extension_owner(program_id, mint_ext_info)?;
+        let layout = {
+            let data = mint_ext_info.data.borrow();
+            let mint_ext = MintExtensionView::new(&data[..])?;
+            if !mint_ext.is_initialized() {
+                return Err(RegistryError::NoRegistry.into());
+            }
+            mint_ext.layout()
+        };
+        if layout == MintExtensionLayout::LATEST {
+            msg!("mint-registry: Mint Extension already uses the latest layout");
+            // stale tails fail the invariants every other instruction
+            // checks, so this is the one place they are repaired
+            return clear_padding(mint_ext_info);
+        }
 
-        let account_infos = create_is_signer_account_infos(&mut meta);
-        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+        migrate_to_latest(payer_info, rent, system_program_info, mint_ext_info)
     }
 
-    // fn do_process_instruction_dups(
-    //     instruction: Instruction,
-    //     account_infos: Vec<AccountInfo>,
-    // ) -> ProgramResult {
-    //     Processor::process(&instruction.program_id, &account_infos, &instruction.data)
-    // }
+    /// Processes a [SyncMint](enum.RegistryInstruction.html) instruction.
+    fn process_sync_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_account_info = next_account_info(account_info_iter)?;
+        let mint_account = Self::unpack_mint(mint_account_info)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let mint_ext_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        let clock = Clock::from_account_info(next_account_info(account_info_iter)?)?;
 
-    fn mintext_minimum_balance() -> u64 {
-        Rent::default().minimum_balance(MintExtension::get_packed_len())
-    }
+        Self::check_mint_extension_address(program_id, mint_account_info, mint_ext_info)?;
+        Self::check_mint_extension_owner(program_id, mint_ext_info)?;
+        {
+            let data = mint_ext_info.data.borrow();
+            let mint_ext = MintExtensionView::new(&data[..])?;
+            Self::check_registered(mint_account_info, &mint_ext)?;
+        }
 
-    fn mint_minimum_balance() -> u64 {
-        Rent::default().minimum_balance(Mint::get_packed_len())
+        // older layouts read the update authority from the mint authority,
+        // which must not follow the mint
+        migrate_to_latest(payer_info, rent, system_program_info, mint_ext_info)?;
+        let mut data = mint_ext_info.data.borrow_mut();
+        let mut mint_ext = MintExtensionView::new(&mut data[..])?;
+        mint_ext.set_mint_authority(&mint_account.mint_authority.unwrap_or_default());
+        mint_ext.set_freeze_authority(&mint_account.freeze_authority.unwrap_or_default());
+        mint_ext.set_supply(mint_account.supply);
+        mint_ext.set_decimals(mint_account.decimals);
+        mint_ext.set_last_sync_slot(clock.slot)
     }
 
-    #[test]
-    fn test_register_mint() {
-        let program_id = Pubkey::new_unique();
-        let symbol = String::from("SYM");
-        let name = String::from("name of mint");
-        let pay_key = Pubkey::new_unique();
-        let mut pay_account = Account::default();
+    /// Processes the instructions signed by the update authority that leave
+    /// symbol and name alone: [SetMetadataField, ClearMetadataField, SetTags,
+    /// SetUpdateAuthority and RevokeUpdateAuthority](enum.RegistryInstruction.html).
+    ///
+    /// Accounts in an older layout are migrated on the way. A change of update
+    /// authority is logged as [AuthorityChanged](../event/enum.RegistryEvent.html).
+    fn process_update_extension<F: FnOnce(&mut MintExtensionView<&mut [u8]>, &mut MintMetadata) -> ProgramResult>(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        update: F,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_account_info = next_account_info(account_info_iter)?;
+        Self::unpack_mint(mint_account_info)?;
+        let mint_owner_info = next_account_info(account_info_iter)?;
+        let mint_ext_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
 
-        let mint_key = Pubkey::new_unique();
-        let mint_authority_key = Pubkey::new_unique();
-        let freeze_authority_key = Pubkey::new_unique();
-        let mint_account_state = Mint {
-            mint_authority: COption::Some(pay_key),
-            supply: 0,
-            decimals:6,
-            is_initialized: true,
-            freeze_authority: COption::None,
+        Self::check_mint_extension_address(program_id, mint_account_info, mint_ext_info)?;
+        Self::check_mint_extension_owner(program_id, mint_ext_info)?;
+        let (payer_info, old_authority) = {
+            let data = mint_ext_info.data.borrow();
+            let mint_ext = MintExtensionView::new(&data[..])?;
+            Self::check_registered(mint_account_info, &mint_ext)?;
+            let old_authority = mint_ext.update_authority();
+            (Self::check_update_authority(mint_account_info.owner, &old_authority, mint_owner_info, account_info_iter.as_slice())?, old_authority)
         };
-        let mut data:[u8;82] = [0;82];
-        mint_account_state.pack_into_slice(&mut data);
-        let mut mint_account= Account::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        mint_account.data = data.to_vec();
 
-        let mint_ext_key = Pubkey::new_unique();
-        let mut mint_ext_account= Account::new(mintext_minimum_balance(), MintExtension::get_packed_len(), &program_id);
+        migrate_to_latest(payer_info, rent, system_program_info, mint_ext_info)?;
+        let mut metadata = MintMetadata::unpack_account(&mint_ext_info.data.borrow())?;
+        let new_authority = {
+            let mut data = mint_ext_info.data.borrow_mut();
+            let mut mint_ext = MintExtensionView::new(&mut data[..])?;
+            update(&mut mint_ext, &mut metadata)?;
+            mint_ext.update_authority()
+        };
+        Self::check_metadata(&metadata)?;
 
+        resize_account(payer_info, rent, metadata.account_len(), system_program_info, mint_ext_info)?;
+        metadata.pack_account(&mut mint_ext_info.data.borrow_mut())?;
 
+        if new_authority != old_authority {
+            RegistryEvent::AuthorityChanged {
+                mint: *mint_account_info.key,
+                old_authority,
+                new_authority,
+                signer: *mint_owner_info.key,
+            }.emit();
+        }
+        Ok(())
+    }
 
-        do_process_instruction(
-            register_mint_instruction(
-                &program_id,
-                &mint_authority_key,
-                &freeze_authority_key,
-                1000000000u64,
-                6u8,
-                &mint_key, 
-                symbol, 
-                name,
-                &pay_key,
-                &mint_ext_key,
-                &[],
-            ).unwrap(),
-            vec![&mut mint_account, &mut pay_account,&mut mint_ext_account],
-        ).unwrap();
+    /// Applies the [ValidationPolicy](../validation/struct.ValidationPolicy.html)
+    /// of the config to symbol and name, then checks the values to store against
+    /// [MAX_SYMBOL_NAME_LEN](../state/constant.MAX_SYMBOL_NAME_LEN.html), the
+    /// room the layout has whatever the policy allows.
+    ///
+    /// Lengths are counted in bytes of the UTF-8 encoding. Instruction strings
+    /// are decoded as UTF-8, so they always end on a character boundary.
+    fn validate_symbol_name(
+        policy: &ValidationPolicy,
+        symbol: &str,
+        name: &str,
+    ) -> Result<(String, String), ProgramError> {
+        Ok((Self::validate_symbol(policy, symbol)?, Self::validate_name(policy, name)?))
+    }
 
-        let mint_ext = MintExtension::unpack_unchecked(&mint_ext_account.data).unwrap();
-        assert_eq!(mint_ext.symbol_len, 3);
-        assert_eq!(mint_ext.symbol[0..3], *("SYM".as_bytes()));
-        assert_eq!(mint_ext.name[0..(mint_ext.name_len as usize)], *("name of mint".as_bytes()));
+    /// Validates a symbol like [validate_symbol_name](#method.validate_symbol_name).
+    fn validate_symbol(policy: &ValidationPolicy, symbol: &str) -> Result<String, ProgramError> {
+        let symbol = policy.validate_symbol(symbol)?;
+        if symbol.len() > MAX_SYMBOL_NAME_LEN {
+            return Err(RegistryError::SymbolToLong.into());
+        }
+        Ok(symbol)
     }
 
-    #[test]
-    fn test_modify_mint() {
-        let program_id = Pubkey::new_unique();
-        let symbol = String::from("SYM2");
-        let name = String::from("name of mint");
-        let pay_key = Pubkey::new_unique();
-        let mut pay_account = Account::default();
+    /// Validates a name like [validate_symbol_name](#method.validate_symbol_name).
+    fn validate_name(policy: &ValidationPolicy, name: &str) -> Result<String, ProgramError> {
+        let name = policy.validate_name(name)?;
+        if name.len() > MAX_SYMBOL_NAME_LEN {
+            return Err(RegistryError::SymbolToLong.into());
+        }
+        Ok(name)
+    }
 
-        let mint_key = Pubkey::new_unique();
-        let mint_account_state = Mint {
-            mint_authority: COption::Some(pay_key),
-            supply: 0,
-            decimals:6,
-            is_initialized: true,
-            freeze_authority: COption::None,
-        };
-        let mut data:[u8;82] = [0;82];
-        mint_account_state.pack_into_slice(&mut data);
-        let mut mint_account= Account::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        mint_account.data = data.to_vec();
+    /// Checks the metadata against the size limits in [state](../state/index.html).
+    fn check_metadata(metadata: &MintMetadata) -> ProgramResult {
+        if metadata.logo_uri.len() > MAX_LOGO_URI_LEN
+            || metadata.website.len() > MAX_WEBSITE_LEN
+            || metadata.description.len() > MAX_DESCRIPTION_LEN
+        {
+            return Err(RegistryError::MetadataTooLong.into());
+        }
+        if metadata.tags.len() > MAX_TAGS {
+            return Err(RegistryError::TooManyTags.into());
+        }
+        if metadata.tags.iter().any(|tag| tag.is_empty() || tag.len() > MAX_TAG_LEN) {
+            return Err(RegistryError::InvalidTag.into());
+        }
+        Ok(())
+    }
 
-        let mint_ext_key = Pubkey::new_unique();
-        let mint_ext_state = MintExtension {
-            is_initialized: true,
-            mint: mint_key,
-            symbol_len:3,
-            symbol:[0;16],
-            name_len:3,
-            name:[0;16],
-        };
-        let mut data:[u8;67] = [0;67];
-        mint_ext_state.pack_into_slice(&mut data);
-        let mut mint_ext_account= Account::new(mintext_minimum_balance(), MintExtension::get_packed_len(), &program_id);
-        mint_ext_account.data = data.to_vec();
+    /// Unpacks the RegistryConfig, rejecting any other account.
+    fn unpack_registry_config(
+        program_id: &Pubkey,
+        config_info: &AccountInfo,
+    ) -> Result<RegistryConfig, ProgramError> {
+        let (config_key, _) = find_registry_config_address(program_id);
+        if config_key != *config_info.key {
+            return Err(RegistryError::InvalidRegistryConfigAddress.into());
+        }
+        if config_info.owner != program_id {
+            return Err(RegistryError::RegistryNotInitialized.into());
+        }
+        RegistryConfig::unpack_account(&config_info.data.borrow())
+            .map_err(|_| RegistryError::RegistryNotInitialized.into())
+    }
 
+    /// Checks that the update authority of the extension signed and returns
+    /// the account paying for the instruction, as
+    /// [validate_authority](#method.validate_authority) does.
+    fn check_update_authority<'a, 'b>(
+        mint_owner: &Pubkey,
+        update_authority: &Pubkey,
+        authority_info: &'b AccountInfo<'a>,
+        signers: &'b [AccountInfo<'a>],
+    ) -> Result<&'b AccountInfo<'a>, ProgramError> {
+        if *update_authority == Pubkey::default() {
+            return Err(RegistryError::NoUpdateAuthority.into());
+        }
+        Self::validate_authority(mint_owner, update_authority, authority_info, signers)
+    }
 
+    /// Checks that the extension holds a registration of the mint, with
+    /// symbol and name lengths that fit their fields and zero bytes past them.
+    ///
+    /// Runs before anything is written, so an account failing it is left
+    /// as it was, for MigrateExtension to repair.
+    fn check_registered(mint_account_info: &AccountInfo, mint_ext: &MintExtensionView<&[u8]>) -> ProgramResult {
+        if !mint_ext.is_initialized() {
+            return Err(RegistryError::NoRegistry.into());
+        }
+        if mint_ext.mint() != *mint_account_info.key {
+            return Err(RegistryError::MintMismatch.into());
+        }
+        mint_ext.check_invariants()
+    }
 
-        do_process_instruction(
-            modify_mint_instruction(
-                &program_id,
-                &mint_key, 
-                symbol, 
-                name,
-                &pay_key,
-                &mint_ext_key,
-                &[],
-            ).unwrap(),
-            vec![&mut mint_account, &mut pay_account,&mut mint_ext_account],
-        ).unwrap();
+    /// Checks that the authority account is the expected one and signed,
+    /// like spl-token's `validate_owner`.
+    ///
+    /// An SPL Token multisig cannot sign, so M of its signers must be among
+    /// the signer accounts instead. The first of them pays for the
+    /// instruction in place of the multisig, which is returned otherwise.
+    /// Only a multisig owned by mint_owner, the token program of the mint,
+    /// is accepted.
+    fn validate_authority<'a, 'b>(
+        mint_owner: &Pubkey,
+        expected: &Pubkey,
+        authority_info: &'b AccountInfo<'a>,
+        signers: &'b [AccountInfo<'a>],
+    ) -> Result<&'b AccountInfo<'a>, ProgramError> {
+        if expected != authority_info.key {
+            return Err(RegistryError::NoAuthority.into());
+        }
+        if authority_info.owner == mint_owner && authority_info.data_len() == Multisig::LEN {
+            let multisig = Multisig::unpack(&authority_info.data.borrow())?;
+            let mut num_signers = 0;
+            let mut matched = [false; MAX_SIGNERS];
+            let mut payer_info = None;
+            for signer in signers.iter() {
+                for (position, key) in multisig.signers.iter().take(multisig.n as usize).enumerate() {
+                    if key == signer.key && !matched[position] {
+                        if !signer.is_signer {
+                            return Err(ProgramError::MissingRequiredSignature);
+                        }
+                        matched[position] = true;
+                        num_signers += 1;
+                        payer_info.get_or_insert(signer);
+                    }
+                }
+            }
+            if num_signers < multisig.m {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            return payer_info.ok_or(ProgramError::MissingRequiredSignature);
+        }
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(authority_info)
+    }
 
-        let mint_ext = MintExtension::unpack_unchecked(&mint_ext_account.data).unwrap();
-        assert_eq!(mint_ext.symbol_len, 4);
-        assert_eq!(mint_ext.symbol[0..4], *("SYM2".as_bytes()));
-        assert_eq!(mint_ext.name[0..(mint_ext.name_len as usize)], *("name of mint".as_bytes()));
+    /// Checks that the admin of the registry signed.
+    fn check_admin(config: &RegistryConfig, admin_info: &AccountInfo) -> ProgramResult {
+        if !admin_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if config.admin != *admin_info.key {
+            return Err(RegistryError::NotAdmin.into());
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_close_mint() {
-        let program_id = Pubkey::new_unique();
-        let pay_key = Pubkey::new_unique();
-        let mut pay_account = Account::default();
+    /// Unpacks the mint, rejecting accounts that are not owned by the token program.
+    fn unpack_mint(mint_account_info: &AccountInfo) -> Result<Mint, ProgramError> {
+        let data = mint_account_info.data.borrow();
+        if *mint_account_info.owner == spl_token::id() {
+            return Mint::unpack_unchecked(&data);
+        }
+        #[cfg(feature = "token-2022")]
+        if *mint_account_info.owner == crate::spl_token_2022::id() {
+            // mints with extensions keep the base layout in front, followed by
+            // padding up to the token account size and the account type
+            const ACCOUNT_TYPE_OFFSET: usize = 165;
+            const ACCOUNT_TYPE_MINT: u8 = 1;
+            if data.len() == Mint::LEN {
+                return Mint::unpack_unchecked(&data);
+            }
+            if data.get(ACCOUNT_TYPE_OFFSET) != Some(&ACCOUNT_TYPE_MINT) {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            return Mint::unpack_unchecked(&data[..Mint::LEN]);
+        }
+        Err(RegistryError::InvalidMintOwner.into())
+    }
 
-        let mint_key = Pubkey::new_unique();
+    /// Checks that the MintExtension account is the one derived from the mint
+    /// and returns its bump seed.
+    fn check_mint_extension_address(
+        program_id: &Pubkey,
+        mint_account_info: &AccountInfo,
+        mint_ext_info: &AccountInfo,
+    ) -> Result<u8, ProgramError> {
+        let (mint_ext_key, bump_seed) = find_mint_extension_address(program_id, mint_account_info.key);
+        if mint_ext_key != *mint_ext_info.key {
+            return Err(RegistryError::InvalidMintExtensionAddress.into());
+        }
+        Ok(bump_seed)
+    }
+
+    /// Checks the MintExtension address like
+    /// [check_mint_extension_address](#method.check_mint_extension_address),
+    /// also accepting an extension of the mint that the program wrote at a
+    /// keypair address before extensions were derived from their mint. Tells
+    /// whether the account is such a legacy one.
+    ///
+    /// Only CloseMint and MigrateExtension take legacy accounts, so that
+    /// their rent can be recovered and their data moved to the latest layout.
+    fn check_legacy_mint_extension_address(
+        program_id: &Pubkey,
+        mint_account_info: &AccountInfo,
+        mint_ext_info: &AccountInfo,
+    ) -> Result<bool, ProgramError> {
+        let (mint_ext_key, _) = find_mint_extension_address(program_id, mint_account_info.key);
+        if mint_ext_key == *mint_ext_info.key {
+            return Ok(false);
+        }
+        if mint_ext_info.owner == program_id {
+            let data = mint_ext_info.data.borrow();
+            if let Ok(mint_ext) = MintExtensionView::new(&data[..]) {
+                if mint_ext.mint() == *mint_account_info.key {
+                    return Ok(true);
+                }
+            }
+        }
+        Err(RegistryError::InvalidMintExtensionAddress.into())
+    }
+
+    /// Checks that the MintExtension account is owned by this program.
+    fn check_mint_extension_owner(program_id: &Pubkey, mint_ext_info: &AccountInfo) -> ProgramResult {
+        if mint_ext_info.owner != program_id {
+            return Err(RegistryError::InvalidMintExtensionOwner.into());
+        }
+        Ok(())
+    }
+}
+
+/// Claims the symbol for the mint, creating its index funded by the payer.
+///
+/// Claiming a symbol the mint already holds changes nothing.
+#[allow(clippy::too_many_arguments)]
+fn claim_symbol<'a>(
+    program_id: &Pubkey,
+    policy: &ValidationPolicy,
+    symbol: &str,
+    mint: &Pubkey,
+    payer: &AccountInfo<'a>,
+    rent: &Rent,
+    system_program: &AccountInfo<'a>,
+    symbol_index_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (symbol_index_key, bump_seed) = find_symbol_index_address(program_id, policy, symbol);
+    if symbol_index_key != *symbol_index_info.key {
+        return Err(RegistryError::InvalidSymbolIndexAddress.into());
+    }
+    if *symbol_index_info.owner == system_program::id() {
+        let symbol_hash = hash(normalize_symbol(policy, symbol).as_bytes());
+        let symbol_index_signer_seeds: &[&[_]] = &[
+            SYMBOL_INDEX_SEED,
+            symbol_hash.as_ref(),
+            &[bump_seed],
+        ];
+        create_pda_account(
+            payer,
+            rent,
+            SymbolIndex::LEN,
+            program_id,
+            system_program,
+            symbol_index_info,
+            symbol_index_signer_seeds,
+        )?;
+    } else {
+        if symbol_index_info.owner != program_id {
+            return Err(RegistryError::InvalidSymbolIndexOwner.into());
+        }
+        if SymbolIndex::unpack_account(&symbol_index_info.data.borrow())?.mint != *mint {
+            return Err(RegistryError::SymbolTaken.into());
+        }
+    }
+    SymbolIndex { mint: *mint }.pack_account(&mut symbol_index_info.data.borrow_mut())
+}
+
+/// Closes the index of a symbol held by the mint, refunding its rent.
+///
+/// Registrations made before symbols were indexed may hold no index, or
+/// one claimed by another mint since; both are left alone.
+fn release_symbol(
+    program_id: &Pubkey,
+    policy: &ValidationPolicy,
+    symbol: &str,
+    mint: &Pubkey,
+    dest: &AccountInfo,
+    symbol_index_info: &AccountInfo,
+) -> ProgramResult {
+    let (symbol_index_key, _) = find_symbol_index_address(program_id, policy, symbol);
+    if symbol_index_key != *symbol_index_info.key {
+        return Err(RegistryError::InvalidSymbolIndexAddress.into());
+    }
+    if *symbol_index_info.owner == system_program::id() {
+        return Ok(());
+    }
+    if symbol_index_info.owner != program_id {
+        return Err(RegistryError::InvalidSymbolIndexOwner.into());
+    }
+    if SymbolIndex::unpack_account(&symbol_index_info.data.borrow())?.mint != *mint {
+        return Ok(());
+    }
+
+    close_account(symbol_index_info, dest)
+}
+
+/// Zeroes the tail earlier versions of ModifyMint left past a shortened
+/// symbol or name, in whatever layout the account uses.
+fn clear_padding(mint_ext_info: &AccountInfo) -> ProgramResult {
+    let mut data = mint_ext_info.data.borrow_mut();
+    MintExtensionView::new(&mut data[..])?.clear_padding();
+    Ok(())
+}
+
+/// Rewrites a MintExtension account in the latest layout, keeping its
+/// metadata, the payer funding the extra rent. Accounts already in the latest
+/// layout are left alone.
+fn migrate_to_latest<'a>(
+    payer: &AccountInfo<'a>,
+    rent: &Rent,
+    system_program: &AccountInfo<'a>,
+    mint_ext_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (mut mint_ext, metadata) = {
+        let data = mint_ext_info.data.borrow();
+        if MintExtensionLayout::detect(&data)? == MintExtensionLayout::LATEST {
+            return Ok(());
+        }
+        (MintExtension::unpack_account(&data)?.0, MintMetadata::unpack_account(&data)?)
+    };
+    // earlier versions of ModifyMint left the tail of a longer symbol or name
+    mint_ext.clear_padding();
+    resize_account(payer, rent, metadata.account_len(), system_program, mint_ext_info)?;
+    let mut data = mint_ext_info.data.borrow_mut();
+    mint_ext.pack_account(MintExtensionLayout::LATEST, &mut data)?;
+    metadata.pack_account(&mut data)
+}
+
+/// Moves all lamports of a program account to dest, then empties it and
+/// assigns it to the system program.
+fn close_account(account_info: &AccountInfo, dest: &AccountInfo) -> ProgramResult {
+    **dest.lamports.borrow_mut() = dest
+        .lamports()
+        .checked_add(account_info.lamports())
+        .ok_or(RegistryError::Overflow)?;
+    **account_info.lamports.borrow_mut() = 0;
+    account_info.realloc(0, false)?;
+    account_info.assign(&system_program::id());
+    Ok(())
+}
+
+/// Returns the metadata string selected by the field.
+fn metadata_field(metadata: &mut MintMetadata, field: MetadataField) -> &mut String {
+    match field {
+        MetadataField::LogoUri => &mut metadata.logo_uri,
+        MetadataField::Website => &mut metadata.website,
+        MetadataField::Description => &mut metadata.description,
+    }
+}
+
+/// Resizes an account owned by the program, keeping it rent exempt.
+///
+/// Growing is paid by the payer; lamports freed by shrinking go back to it.
+fn resize_account<'a>(
+    payer: &AccountInfo<'a>,
+    rent: &Rent,
+    space: usize,
+    system_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+) -> ProgramResult {
+    let old_space = account.data_len();
+    if space == old_space {
+        return Ok(());
+    }
+    let minimum_balance = rent.minimum_balance(space);
+    let required_lamports = minimum_balance.saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    account.realloc(space, false)?;
+
+    let excess_lamports = account.lamports().saturating_sub(minimum_balance);
+    if space < old_space && excess_lamports > 0 {
+        **account.lamports.borrow_mut() -= excess_lamports;
+        **payer.lamports.borrow_mut() = payer
+            .lamports()
+            .checked_add(excess_lamports)
+            .ok_or(RegistryError::Overflow)?;
+    }
+    Ok(())
+}
+
+/// Creates a program derived account funded by the payer.
+///
+/// Lamports already sitting at the address are kept, so a transfer to the
+/// derived address cannot block its creation.
+fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    rent: &Rent,
+    space: usize,
+    owner: &Pubkey,
+    system_program: &AccountInfo<'a>,
+    new_pda_account: &AccountInfo<'a>,
+    new_pda_signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    if new_pda_account.lamports() > 0 {
+        let required_lamports = rent
+            .minimum_balance(space)
+            .max(1)
+            .saturating_sub(new_pda_account.lamports());
+
+        if required_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, new_pda_account.key, required_lamports),
+                &[payer.clone(), new_pda_account.clone(), system_program.clone()],
+            )?;
+        }
+
+        invoke_signed(
+            &system_instruction::allocate(new_pda_account.key, space as u64),
+            &[new_pda_account.clone(), system_program.clone()],
+            &[new_pda_signer_seeds],
+        )?;
+
+        invoke_signed(
+            &system_instruction::assign(new_pda_account.key, owner),
+            &[new_pda_account.clone(), system_program.clone()],
+            &[new_pda_signer_seeds],
+        )
+    } else {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                new_pda_account.key,
+                rent.minimum_balance(space).max(1),
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), new_pda_account.clone(), system_program.clone()],
+            &[new_pda_signer_seeds],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{event::EVENT_LOG_PREFIX, instruction::*, spl_token_2022, state::AccountType, validation::Whitespace};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    //use solana_program::{clock::Epoch, instruction::Instruction, sysvar::rent};
+    use solana_program::{
+        entrypoint::MAX_PERMITTED_DATA_INCREASE,
+        instruction::Instruction,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+        program_utils::limited_deserialize,
+        system_instruction::SystemInstruction,
+    };
+    use solana_sdk::account::{create_account_for_test, Account};
+    use std::{cell::RefCell, sync::Once};
+
+    static SET_SYSCALL_STUBS: Once = Once::new();
+
+    thread_local! {
+        /// data lines logged by the last instruction, as the runtime shows them
+        static DATA_LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Executes the system program instructions the processor invokes and
+    /// keeps the data it logs.
+    struct TestSyscallStubs;
+    impl SyscallStubs for TestSyscallStubs {
+        fn sol_log_data(&self, fields: &[&[u8]]) {
+            let encoded = fields.iter().map(|field| STANDARD.encode(field)).collect::<Vec<_>>();
+            let log = format!("{}{}", EVENT_LOG_PREFIX, encoded.join(" "));
+            DATA_LOGS.with(|logs| logs.borrow_mut().push(log));
+        }
+
+
+        fn sol_invoke_signed(
+            &self,
+            instruction: &Instruction,
+            account_infos: &[AccountInfo],
+            _signers_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            assert_eq!(instruction.program_id, system_program::id());
+            let account_info = |index: usize| {
+                account_infos
+                    .iter()
+                    .find(|info| *info.key == instruction.accounts[index].pubkey)
+                    .unwrap()
+            };
+            match limited_deserialize(&instruction.data, 1024).unwrap() {
+                SystemInstruction::CreateAccount { lamports, space, owner } => {
+                    let to = account_info(1);
+                    if to.lamports() > 0 || !to.data_is_empty() {
+                        return Err(ProgramError::AccountAlreadyInitialized);
+                    }
+                    transfer_lamports(account_info(0), to, lamports)?;
+                    to.realloc(space as usize, true)?;
+                    to.assign(&owner);
+                }
+                SystemInstruction::Transfer { lamports } => {
+                    transfer_lamports(account_info(0), account_info(1), lamports)?;
+                }
+                SystemInstruction::Allocate { space } => {
+                    account_info(0).realloc(space as usize, true)?;
+                }
+                SystemInstruction::Assign { owner } => {
+                    account_info(0).assign(&owner);
+                }
+                instruction => panic!("unexpected system instruction {:?}", instruction),
+            }
+            Ok(())
+        }
+    }
+
+    /// Events logged by the last instruction.
+    fn logged_events() -> Vec<RegistryEvent> {
+        DATA_LOGS.with(|logs| {
+            logs.borrow()
+                .iter()
+                .map(|log| RegistryEvent::decode_log(log).unwrap().unwrap())
+                .collect()
+        })
+    }
+
+    fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+        if !from.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let from_lamports = from.lamports().checked_sub(lamports).ok_or(ProgramError::InsufficientFunds)?;
+        **from.lamports.borrow_mut() = from_lamports;
+        **to.lamports.borrow_mut() += lamports;
+        Ok(())
+    }
+
+    /// Key preceded by the original data length, as serialized by the runtime.
+    #[repr(C)]
+    struct SerializedKey {
+        original_data_len: u32,
+        key: Pubkey,
+    }
+
+    /// Account laid out the way the runtime serializes it, so that
+    /// `AccountInfo::realloc` can grow the data in place.
+    struct SerializedAccount {
+        key: SerializedKey,
+        is_signer: bool,
+        is_writable: bool,
+        lamports: u64,
+        owner: Pubkey,
+        /// data length, data and realloc padding
+        data: Vec<u8>,
+    }
+    impl SerializedAccount {
+        fn new(key: &Pubkey, is_signer: bool, is_writable: bool, account: &Account) -> Self {
+            let mut data = Vec::with_capacity(8 + account.data.len() + MAX_PERMITTED_DATA_INCREASE);
+            data.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            data.extend_from_slice(&account.data);
+            data.resize(data.capacity(), 0);
+            Self {
+                key: SerializedKey {
+                    original_data_len: account.data.len() as u32,
+                    key: *key,
+                },
+                is_signer,
+                is_writable,
+                lamports: account.lamports,
+                owner: account.owner,
+                data,
+            }
+        }
+
+        fn data_len(&self) -> usize {
+            let mut len = [0u8; 8];
+            len.copy_from_slice(&self.data[..8]);
+            u64::from_le_bytes(len) as usize
+        }
+
+        fn account_info(&mut self) -> AccountInfo<'_> {
+            let data_len = self.data_len();
+            AccountInfo::new(
+                &self.key.key,
+                self.is_signer,
+                self.is_writable,
+                &mut self.lamports,
+                &mut self.data[8..8 + data_len],
+                &self.owner,
+                false,
+                0,
+            )
+        }
+
+        fn write_back(&self, account: &mut Account) {
+            account.lamports = self.lamports;
+            account.owner = self.owner;
+            account.data = self.data[8..8 + self.data_len()].to_vec();
+        }
+    }
+
+    fn do_process_instruction(
+        instruction: Instruction,
+        accounts: Vec<&mut Account>,
+    ) -> ProgramResult {
+        SET_SYSCALL_STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(TestSyscallStubs));
+        });
+        DATA_LOGS.with(|logs| logs.borrow_mut().clear());
+        let mut serialized = instruction
+            .accounts
+            .iter()
+            .zip(accounts.iter())
+            .map(|(account_meta, account)| {
+                SerializedAccount::new(&account_meta.pubkey, account_meta.is_signer, account_meta.is_writable, account)
+            })
+            .collect::<Vec<_>>();
+
+        let result = {
+            let account_infos = serialized
+                .iter_mut()
+                .map(|account| account.account_info())
+                .collect::<Vec<_>>();
+            Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+        };
+        // like the runtime, keep changes only when the instruction succeeds
+        if result.is_ok() {
+            for (account, serialized) in accounts.into_iter().zip(serialized.iter()) {
+                serialized.write_back(account);
+            }
+        }
+        result
+    }
+
+    fn mintext_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(MintExtension::account_len(MintExtensionLayout::LATEST))
+    }
+
+    fn symbol_index_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(SymbolIndex::LEN)
+    }
+
+    fn mint_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(Mint::get_packed_len())
+    }
+
+    fn rent_sysvar() -> Account {
+        create_account_for_test(&Rent::default())
+    }
+
+    fn new_registry_config_account(program_id: &Pubkey) -> Account {
+        new_registry_config_account_with(program_id, RegistryConfig::default())
+    }
+
+    fn new_registry_config_account_with(program_id: &Pubkey, config: RegistryConfig) -> Account {
+        let mut config_account = Account::new(
+            Rent::default().minimum_balance(config.account_len()),
+            config.account_len(),
+            program_id,
+        );
+        config.pack_account(&mut config_account.data).unwrap();
+        config_account
+    }
+
+    fn new_mint_account(owner: &Pubkey, mint_authority: &Pubkey) -> Account {
         let mint_account_state = Mint {
-            mint_authority: COption::Some(pay_key),
+            mint_authority: COption::Some(*mint_authority),
             supply: 0,
             decimals:6,
             is_initialized: true,
             freeze_authority: COption::None,
         };
-        let mut data:[u8;82] = [0;82];
-        mint_account_state.pack_into_slice(&mut data);
-        let mut mint_account= Account::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        mint_account.data = data.to_vec();
+        let mut mint_account = Account::new(mint_minimum_balance(), Mint::get_packed_len(), owner);
+        mint_account_state.pack_into_slice(&mut mint_account.data);
+        mint_account
+    }
+
+    fn padded(value: &str) -> [u8; MAX_SYMBOL_NAME_LEN] {
+        let mut buf = [0; MAX_SYMBOL_NAME_LEN];
+        buf[..value.len()].copy_from_slice(value.as_bytes());
+        buf
+    }
 
-        let mint_ext_key = Pubkey::new_unique();
+    fn new_mint_ext_account(program_id: &Pubkey, mint_key: &Pubkey, authority: &Pubkey) -> Account {
+        new_mint_ext_account_with_layout(program_id, mint_key, authority, MintExtensionLayout::LATEST)
+    }
+
+    fn new_mint_ext_account_with_layout(
+        program_id: &Pubkey,
+        mint_key: &Pubkey,
+        authority: &Pubkey,
+        layout: MintExtensionLayout,
+    ) -> Account {
         let mint_ext_state = MintExtension {
+            mint_authority: *authority,
+            update_authority: *authority,
             is_initialized: true,
-            mint: mint_key,
+            mint: *mint_key,
             symbol_len:3,
-            symbol:[0;16],
+            symbol:padded("SYM"),
             name_len:3,
-            name:[0;16],
+            name:padded("SYM"),
+            ..MintExtension::default()
         };
-        let mut data:[u8;67] = [0;67];
-        mint_ext_state.pack_into_slice(&mut data);
-        let mut mint_ext_account= Account::new(mintext_minimum_balance(), MintExtension::get_packed_len(), &program_id);
-        mint_ext_account.data = data.to_vec();
+        let len = MintExtension::account_len(layout);
+        let mut mint_ext_account = Account::new(Rent::default().minimum_balance(len), len, program_id);
+        mint_ext_state.pack_account(layout, &mut mint_ext_account.data).unwrap();
+        mint_ext_account
+    }
 
+    #[test]
+    fn test_register_mint() {
+        let program_id = Pubkey::new_unique();
+        let symbol = String::from("SYM");
+        let name = String::from("name of mint");
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(mintext_minimum_balance() + symbol_index_minimum_balance(), 0, &system_program::id());
+
+        let mint_key = Pubkey::new_unique();
+        let freeze_authority_key = Pubkey::new_unique();
+        let mint_account_state = Mint {
+            mint_authority: COption::Some(pay_key),
+            supply: 1000000000,
+            decimals:6,
+            is_initialized: true,
+            freeze_authority: COption::Some(freeze_authority_key),
+        };
+        let mut data:[u8;82] = [0;82];
+        mint_account_state.pack_into_slice(&mut data);
+        let mut mint_account= Account::new(mint_minimum_balance(), Mint::get_packed_len(), &spl_token::id());
+        mint_account.data = data.to_vec();
 
+        let (mint_ext_key, _) = find_mint_extension_address(&program_id, &mint_key);
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
 
         do_process_instruction(
-            close_mint_instruction(
+            register_mint_instruction(
                 &program_id,
+                &ValidationPolicy::default(),
                 &mint_key, 
+                symbol, 
+                name,
                 &pay_key,
-                &mint_ext_key,
                 &[],
             ).unwrap(),
-            vec![&mut mint_ext_account, &mut pay_account,&mut mint_account],
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
         ).unwrap();
-        assert_eq!(mint_ext_account.lamports, 0);
+
+        assert_eq!(mint_ext_account.owner, program_id);
+        assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+        assert_eq!(pay_account.lamports, 0);
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.symbol_len, 3);
+        assert_eq!(mint_ext.symbol[0..3], *("SYM".as_bytes()));
+        assert_eq!(mint_ext.name[0..(mint_ext.name_len as usize)], *("name of mint".as_bytes()));
+        assert_eq!(mint_ext.mint, mint_key);
+        assert_eq!(mint_ext.mint_authority, pay_key);
+        assert_eq!(mint_ext.freeze_authority, freeze_authority_key);
+        assert_eq!(mint_ext.supply, 1000000000);
+        assert_eq!(mint_ext.decimals, 6);
+
+        // the extension lives at the address derived from the mint only
+        let mut instruction = register_mint_instruction(
+            &program_id,
+            &ValidationPolicy::default(),
+            &mint_key,
+            String::from("SYM"),
+            String::from("name of mint"),
+            &pay_key,
+            &[],
+        ).unwrap();
+        instruction.accounts[3].pubkey = Pubkey::new_unique();
+        assert_ne!(instruction.accounts[3].pubkey, mint_ext_key);
+        let mut pay_account = Account::new(mintext_minimum_balance() + symbol_index_minimum_balance(), 0, &system_program::id());
+        let mut mint_ext_account = Account::default();
+        assert_eq!(
+            Err(RegistryError::InvalidMintExtensionAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+    }
+
+    #[test]
+    fn test_mint_mismatch() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let other_mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut pay_account = Account::new(mintext_minimum_balance() + symbol_index_minimum_balance(), 0, &system_program::id());
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // the instruction names another mint than the account passed in
+        let mut instruction = register_mint_instruction(
+            &program_id,
+            &ValidationPolicy::default(),
+            &other_mint_key,
+            String::from("SYM"),
+            String::from("name of mint"),
+            &pay_key,
+            &[],
+        ).unwrap();
+        instruction.accounts[1].pubkey = mint_key;
+        instruction.accounts[3].pubkey = find_mint_extension_address(&program_id, &mint_key).0;
+        assert_eq!(
+            Err(RegistryError::MintMismatch.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+
+        // an extension recording another mint
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &other_mint_key, &pay_key);
+        assert_eq!(
+            Err(RegistryError::MintMismatch.into()),
+            do_process_instruction(
+                modify_mint_instruction(
+                    &program_id,
+                    &ValidationPolicy::default(),
+                    &mint_key,
+                    "SYM",
+                    String::from("SYM2"),
+                    String::from("name of mint"),
+                    &pay_key,
+                    &[],
+                ).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::MintMismatch.into()),
+            do_process_instruction(
+                close_mint_instruction(
+                    &program_id,
+                    &ValidationPolicy::default(),
+                    &mint_key,
+                    "SYM",
+                    &pay_key,
+                    &pay_key,
+                    &[],
+                ).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account.clone(), &mut mint_ext_account, &mut Account::default(), &mut pay_account],
+            )
+        );
+    }
+
+    #[test]
+    fn test_register_mint_borsh() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(
+            mintext_minimum_balance() + 2 * symbol_index_minimum_balance(),
+            0,
+            &system_program::id(),
+        );
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        let mut instruction = register_mint_instruction(
+            &program_id,
+            &ValidationPolicy::default(),
+            &mint_key,
+            String::from("SYM"),
+            String::from("name of mint"),
+            &pay_key,
+            &[],
+        ).unwrap();
+        instruction.data = RegistryInstruction::unpack(&instruction.data).unwrap().pack_borsh();
+        do_process_instruction(
+            instruction,
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext.mint, mint_key);
+        assert_eq!(mint_ext.symbol[0..3], *("SYM".as_bytes()));
+
+        do_process_instruction(
+            modify_mint_instruction(
+                &program_id,
+                &ValidationPolicy::default(),
+                &mint_key,
+                "SYM",
+                String::from("SYM2"),
+                String::from("name of mint"),
+                &pay_key,
+                &[],
+            ).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext.symbol[0..4], *("SYM2".as_bytes()));
+    }
+
+    #[test]
+    fn test_register_mint_prefunded_address() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(mintext_minimum_balance() + symbol_index_minimum_balance(), 0, &system_program::id());
+
+        let mint_key = Pubkey::new_unique();
+        let mint_account_state = Mint {
+            mint_authority: COption::Some(pay_key),
+            supply: 0,
+            decimals:6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut mint_account= Account::new(mint_minimum_balance(), Mint::get_packed_len(), &spl_token::id());
+        mint_account_state.pack_into_slice(&mut mint_account.data);
+
+        // someone already sent lamports to the derived address
+        let mut mint_ext_account = Account::new(1000, 0, &system_program::id());
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        do_process_instruction(
+            register_mint_instruction(
+                &program_id,
+                &ValidationPolicy::default(),
+                &mint_key,
+                String::from("SYM"),
+                String::from("name of mint"),
+                &pay_key,
+                &[],
+            ).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+
+        assert_eq!(mint_ext_account.owner, program_id);
+        assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+        assert_eq!(pay_account.lamports, 1000);
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert!(mint_ext.is_initialized);
+    }
+
+    #[test]
+    fn test_register_mint_rent_exempt() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(symbol_index_minimum_balance(), 0, &system_program::id());
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+
+        // an extension left allocated by an earlier close, short of rent
+        let len = MintExtension::account_len(MintExtensionLayout::LATEST);
+        let mut mint_ext_account = Account::new(mintext_minimum_balance() - 1, len, &program_id);
+        MintExtension::default().pack_account(MintExtensionLayout::LATEST, &mut mint_ext_account.data).unwrap();
+
+        assert_eq!(
+            Err(RegistryError::NotRentExempt.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name of mint"), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default()],
+            )
+        );
+
+        mint_ext_account.lamports += 1;
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name of mint"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default()],
+        ).unwrap();
+        assert!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.is_initialized);
+    }
+
+    #[test]
+    fn test_modify_mint() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(symbol_index_minimum_balance(), 0, &system_program::id());
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+
+        do_process_instruction(
+            modify_mint_instruction(
+                &program_id,
+                &ValidationPolicy::default(),
+                &mint_key, 
+                "SYM",
+                String::from("SYM2"),
+                String::from("name of mint"),
+                &pay_key,
+                &[],
+            ).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.symbol_len, 4);
+        assert_eq!(mint_ext.symbol[0..4], *("SYM2".as_bytes()));
+        assert_eq!(mint_ext.name[0..(mint_ext.name_len as usize)], *("name of mint".as_bytes()));
     }
 
+    #[test]
+    fn test_update_mint() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+        MintExtensionView::new(&mut mint_ext_account.data[..]).unwrap().set_verified(true).unwrap();
+
+        // an update changing nothing is rejected, expectations or not
+        let data = mint_ext_account.data.clone();
+        for update in [MintUpdate::default(), MintUpdate::default().expect_symbol(String::from("SYM"))] {
+            assert_eq!(
+                Err(RegistryError::InvalidInstruction.into()),
+                do_process_instruction(
+                    update_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", update, &pay_key, &[]).unwrap(),
+                    vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default(), &mut Account::default()],
+                )
+            );
+        }
+        assert_eq!(mint_ext_account.data, data);
 
-}
\ No newline at end of file
+        let mut update = |current_symbol: &str, update: MintUpdate, pay_account: &mut Account| {
+            do_process_instruction(
+                update_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, current_symbol, update, &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default(), &mut Account::default()],
+            )?;
+            Ok::<_, ProgramError>(mint_ext_account.data.clone())
+        };
+
+
+        // the current value differs from the expected one
+        assert_eq!(
+            Err(RegistryError::ValueMismatch.into()),
+            update("SYM", MintUpdate::default().name(String::from("Renamed")).expect_symbol(String::from("OTHER")), &mut pay_account)
+        );
+        assert_eq!(
+            Err(RegistryError::ValueMismatch.into()),
+            update("SYM", MintUpdate::default().name(String::from("Renamed")).expect_name(String::from("OTHER")), &mut pay_account)
+        );
+
+        // a new name keeps the symbol and its index, so no rent is needed
+        let data = update(
+            "SYM",
+            MintUpdate::default().name(String::from("Renamed")).expect_symbol(String::from("SYM")).expect_name(String::from("SYM")),
+            &mut pay_account,
+        ).unwrap();
+        let view = MintExtensionView::new(&data[..]).unwrap();
+        assert_eq!(view.symbol(), Ok("SYM"));
+        assert_eq!(view.name(), Ok("Renamed"));
+        assert!(!view.verified());
+
+        assert_eq!(
+            Err(RegistryError::InvalidCharacter.into()),
+            update("SYM", MintUpdate::default().symbol(String::from("SYM\u{0}")), &mut pay_account)
+        );
+
+        // a new symbol claims its index and keeps the name
+        let mut pay_account = Account::new(symbol_index_minimum_balance(), 0, &system_program::id());
+        let data = update(
+            "SYM",
+            MintUpdate::default().symbol(String::from("SYM2")).expect_name(String::from("Renamed")),
+            &mut pay_account,
+        ).unwrap();
+        let view = MintExtensionView::new(&data[..]).unwrap();
+        assert_eq!(view.symbol(), Ok("SYM2"));
+        assert_eq!(view.name(), Ok("Renamed"));
+        assert_eq!(pay_account.lamports, 0);
+    }
+
+    #[test]
+    fn test_modify_mint_clears_padding() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(2 * symbol_index_minimum_balance(), 0, &system_program::id());
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+
+        let mut rename = |symbol: &str, name: &str, old_symbol: &str| {
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, old_symbol, String::from(symbol), String::from(name), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default(), &mut Account::default()],
+            ).unwrap();
+            mint_ext_account.data.clone()
+        };
+        rename("LONGSYMBOL", "A rather long name of mint", "SYM");
+        let data = rename("LS", "Short", "LONGSYMBOL");
+
+        let view = MintExtensionView::new(&data[..]).unwrap();
+        assert_eq!(view.check_invariants(), Ok(()));
+        let mint_ext = MintExtension::unpack_account(&data).unwrap().0;
+        assert_eq!(mint_ext.symbol, padded("LS"));
+        assert_eq!(mint_ext.name, padded("Short"));
+    }
+
+    #[test]
+    fn test_close_mint() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::default();
+        let dest_key = Pubkey::new_unique();
+        let mut dest_account = Account::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+        let mint_ext_key = find_mint_extension_address(&program_id, &mint_key).0;
+
+        // the authority of another mint cannot close this extension
+        let other_key = Pubkey::new_unique();
+        let other_mint_key = Pubkey::new_unique();
+        let mut other_mint_account = new_mint_account(&spl_token::id(), &other_key);
+        let mut instruction = close_mint_instruction(&program_id, &ValidationPolicy::default(), &other_mint_key, "SYM", &other_key, &other_key, &[]).unwrap();
+        instruction.accounts[3].pubkey = mint_ext_key;
+        assert_eq!(
+            Err(RegistryError::InvalidMintExtensionAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut other_mint_account, &mut Account::default(), &mut mint_ext_account, &mut Account::default(), &mut Account::default()],
+            )
+        );
+
+        // the rent cannot stay in the closed accounts
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &mint_ext_key, &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut Account::default()],
+            )
+        );
+
+        do_process_instruction(
+            close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &dest_key, &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut dest_account],
+        ).unwrap();
+        assert_eq!(mint_ext_account.lamports, 0);
+        assert_eq!(pay_account.lamports, 0);
+        assert_eq!(dest_account.lamports, mintext_minimum_balance());
+        assert_eq!(mint_ext_account.owner, system_program::id());
+        assert!(mint_ext_account.data.is_empty());
+
+        assert_eq!(
+            Err(RegistryError::InvalidMintExtensionOwner.into()),
+            do_process_instruction(
+                close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &dest_key, &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut dest_account],
+            )
+        );
+    }
+
+    #[test]
+    fn test_close_mint_reuse() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let funds = 10 * (mintext_minimum_balance() + symbol_index_minimum_balance());
+        let mut pay_account = Account::new(funds, 0, &system_program::id());
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut symbol_index_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("OLD"), String::from("old name"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut symbol_index_account],
+        ).unwrap();
+        do_process_instruction(
+            set_metadata_field_instruction(&program_id, &mint_key, MetadataField::Website, String::from("https://example.com"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+
+        // the instructions below run as one transaction: the refund pays for
+        // the new registration of the same address
+        do_process_instruction(
+            close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "OLD", &pay_key, &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account.clone(), &mut mint_ext_account, &mut symbol_index_account, &mut pay_account],
+        ).unwrap();
+        assert_eq!(
+            Err(RegistryError::InvalidMintExtensionOwner.into()),
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "OLD", String::from("OLD"), String::from("revived"), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        let mut new_symbol_index_account = Account::default();
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("NEW"), String::from("new name"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut new_symbol_index_account],
+        ).unwrap();
+
+        // every lamport of the first registration came back
+        assert_eq!(pay_account.lamports, funds - mintext_minimum_balance() - symbol_index_minimum_balance());
+        assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+        assert_eq!(mint_ext_account.data.len(), MintExtension::account_len(MintExtensionLayout::LATEST));
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.symbol().unwrap(), "NEW");
+        assert_eq!(mint_ext.name().unwrap(), "new name");
+        assert_eq!(MintMetadata::unpack_account(&mint_ext_account.data).unwrap(), MintMetadata::default());
+        assert_eq!(symbol_index_account.owner, system_program::id());
+    }
+
+    #[test]
+    fn test_mint_extension_owner() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(mintext_minimum_balance() + symbol_index_minimum_balance(), 0, &system_program::id());
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        for owner in &[system_program::id(), Pubkey::new_unique()] {
+            // a well formed extension held by the wrong owner
+            let mut mint_ext_account = new_mint_ext_account(owner, &mint_key, &pay_key);
+            assert_eq!(
+                Err(RegistryError::InvalidMintExtensionOwner.into()),
+                do_process_instruction(
+                    modify_mint_instruction(
+                        &program_id,
+                        &ValidationPolicy::default(),
+                        &mint_key,
+                        "SYM",
+                        String::from("SYM2"),
+                        String::from("name of mint"),
+                        &pay_key,
+                        &[],
+                    ).unwrap(),
+                    vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+                )
+            );
+            assert_eq!(
+                Err(RegistryError::InvalidMintExtensionOwner.into()),
+                do_process_instruction(
+                    close_mint_instruction(
+                        &program_id,
+                        &ValidationPolicy::default(),
+                        &mint_key,
+                        "SYM",
+                        &pay_key,
+                        &pay_key,
+                        &[],
+                    ).unwrap(),
+                    vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account.clone(), &mut mint_ext_account, &mut Account::default(), &mut pay_account],
+                )
+            );
+            assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+        }
+
+        let mut mint_ext_account = Account::new(mintext_minimum_balance(), MintExtension::get_packed_len(), &Pubkey::new_unique());
+        assert_eq!(
+            Err(RegistryError::InvalidMintExtensionOwner.into()),
+            do_process_instruction(
+                register_mint_instruction(
+                    &program_id,
+                    &ValidationPolicy::default(),
+                    &mint_key,
+                    String::from("SYM"),
+                    String::from("name of mint"),
+                    &pay_key,
+                    &[],
+                ).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+        assert_eq!(pay_account.lamports, mintext_minimum_balance() + symbol_index_minimum_balance());
+
+        // an extension already created by the program cannot be registered twice
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+        assert_eq!(
+            Err(RegistryError::AlreadRegistry.into()),
+            do_process_instruction(
+                register_mint_instruction(
+                    &program_id,
+                    &ValidationPolicy::default(),
+                    &mint_key,
+                    String::from("SYM"),
+                    String::from("name of mint"),
+                    &pay_key,
+                    &[],
+                ).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+    }
+
+    #[test]
+    fn test_spoofed_mint_owner() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        // a mint layout written by the attacker's own program
+        let mut mint_account = new_mint_account(&Pubkey::new_unique(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut pay_account = Account::new(mintext_minimum_balance() + symbol_index_minimum_balance(), 0, &system_program::id());
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        assert_eq!(
+            Err(RegistryError::InvalidMintOwner.into()),
+            do_process_instruction(
+                register_mint_instruction(
+                    &program_id,
+                    &ValidationPolicy::default(),
+                    &mint_key,
+                    String::from("USDC"),
+                    String::from("USD Coin"),
+                    &pay_key,
+                    &[],
+                ).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+        assert_eq!(mint_ext_account.owner, system_program::id());
+
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+        assert_eq!(
+            Err(RegistryError::InvalidMintOwner.into()),
+            do_process_instruction(
+                modify_mint_instruction(
+                    &program_id,
+                    &ValidationPolicy::default(),
+                    &mint_key,
+                    "SYM",
+                    String::from("USDC"),
+                    String::from("USD Coin"),
+                    &pay_key,
+                    &[],
+                ).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+
+        assert_eq!(
+            Err(RegistryError::InvalidMintOwner.into()),
+            do_process_instruction(
+                close_mint_instruction(
+                    &program_id,
+                    &ValidationPolicy::default(),
+                    &mint_key,
+                    "SYM",
+                    &pay_key,
+                    &pay_key,
+                    &[],
+                ).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account.clone(), &mut mint_ext_account, &mut Account::default(), &mut pay_account],
+            )
+        );
+        assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+    }
+
+    #[test]
+    fn test_token_2022_mint() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token_2022::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+        let mut pay_account = Account::new(2 * symbol_index_minimum_balance(), 0, &system_program::id());
+
+        let result = do_process_instruction(
+            modify_mint_instruction(
+                &program_id,
+                &ValidationPolicy::default(),
+                &mint_key,
+                "SYM",
+                String::from("SYM2"),
+                String::from("name of mint"),
+                &pay_key,
+                &[],
+            ).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default(), &mut Account::default()],
+        );
+        if cfg!(feature = "token-2022") {
+            assert_eq!(result, Ok(()));
+        } else {
+            assert_eq!(result, Err(RegistryError::InvalidMintOwner.into()));
+        }
+
+        // a token-2022 mint with extensions keeps the base mint in front
+        #[cfg(feature = "token-2022")]
+        {
+            let mut data = mint_account.data.clone();
+            data.resize(170, 0);
+            data[165] = 1;
+            mint_account.data = data;
+            do_process_instruction(
+                modify_mint_instruction(
+                    &program_id,
+                    &ValidationPolicy::default(),
+                    &mint_key,
+                    "SYM2",
+                    String::from("SYM3"),
+                    String::from("name of mint"),
+                    &pay_key,
+                    &[],
+                ).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default(), &mut Account::default()],
+            ).unwrap();
+
+            // token accounts are not mints
+            mint_account.data[165] = 2;
+            assert_eq!(
+                Err(ProgramError::InvalidAccountData),
+                do_process_instruction(
+                    modify_mint_instruction(
+                        &program_id,
+                        &ValidationPolicy::default(),
+                        &mint_key,
+                        "SYM3",
+                        String::from("SYM4"),
+                        String::from("name of mint"),
+                        &pay_key,
+                        &[],
+                    ).unwrap(),
+                    vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default(), &mut Account::default()],
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_sync_mint() {
+        let program_id = Pubkey::new_unique();
+        let authority_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &authority_key);
+        // registered before update authorities had a field of their own
+        let mut mint_ext_account =
+            new_mint_ext_account_with_layout(&program_id, &mint_key, &authority_key, MintExtensionLayout::V2);
+
+        let new_authority_key = Pubkey::new_unique();
+        let freeze_authority_key = Pubkey::new_unique();
+        Mint {
+            mint_authority: COption::Some(new_authority_key),
+            supply: 500,
+            decimals: 9,
+            is_initialized: true,
+            freeze_authority: COption::Some(freeze_authority_key),
+        }.pack_into_slice(&mut mint_account.data);
+
+        // anyone may sync, paying for the migration
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(mintext_minimum_balance(), 0, &system_program::id());
+        let clock = |slot| create_account_for_test(&Clock { slot, ..Clock::default() });
+        do_process_instruction(
+            sync_mint_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut clock(77)],
+        ).unwrap();
+
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext.mint_authority, new_authority_key);
+        assert_eq!(mint_ext.freeze_authority, freeze_authority_key);
+        assert_eq!(mint_ext.supply, 500);
+        assert_eq!(mint_ext.decimals, 9);
+        assert_eq!(mint_ext.last_sync_slot, 77);
+        // the update authority stays with the registrant
+        assert_eq!(mint_ext.update_authority, authority_key);
+        assert_eq!(mint_ext.symbol().unwrap(), "SYM");
+
+        // authorities revoked on the mint are revoked in the cache
+        Mint {
+            mint_authority: COption::None,
+            supply: 500,
+            decimals: 9,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }.pack_into_slice(&mut mint_account.data);
+        let mut pay_account = Account::default();
+        do_process_instruction(
+            sync_mint_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut clock(90)],
+        ).unwrap();
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.mint_authority, Pubkey::default());
+        assert_eq!(mint_ext.freeze_authority, Pubkey::default());
+        assert_eq!(mint_ext.update_authority, authority_key);
+        assert_eq!(mint_ext.last_sync_slot, 90);
+
+        // the extension of another mint is not synced from this one
+        let other_mint_key = Pubkey::new_unique();
+        let mut instruction = sync_mint_instruction(&program_id, &other_mint_key, &pay_key).unwrap();
+        instruction.accounts[3].pubkey = find_mint_extension_address(&program_id, &mint_key).0;
+        assert_eq!(
+            Err(RegistryError::InvalidMintExtensionAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut clock(91)],
+            )
+        );
+    }
+
+    #[test]
+    fn test_dirty_extension() {
+        let program_id = Pubkey::new_unique();
+        let authority_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &authority_key);
+        let mut mint_ext_account =
+            new_mint_ext_account_with_layout(&program_id, &mint_key, &authority_key, MintExtensionLayout::LATEST);
+        let clean_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+
+        // names shortened by earlier versions of ModifyMint kept a stale tail,
+        // which is rejected before anything is written
+        let mut dirty_ext = clean_ext;
+        dirty_ext.name = padded("SYM and the rest");
+        dirty_ext.pack_account(MintExtensionLayout::LATEST, &mut mint_ext_account.data).unwrap();
+        assert_eq!(MintExtensionView::new(&mint_ext_account.data[..]).unwrap().check_invariants(), Err(ProgramError::InvalidAccountData));
+        let dirty_data = mint_ext_account.data.clone();
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                sync_mint_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut Account::default(), &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut create_account_for_test(&Clock::default())],
+            )
+        );
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                update_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", MintUpdate::default().name(String::from("name")), &authority_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut Account::default(), &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut Account::default(), &mut Account::default()],
+            )
+        );
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &pay_key, &authority_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut Account::default(), &mut mint_ext_account, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        assert_eq!(mint_ext_account.data, dirty_data);
+
+        // MigrateExtension repairs them, whoever pays
+        do_process_instruction(
+            migrate_extension_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut Account::default(), &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar()],
+        ).unwrap();
+        assert_eq!(MintExtensionView::new(&mint_ext_account.data[..]).unwrap().check_invariants(), Ok(()));
+        assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.name, padded("SYM"));
+        do_process_instruction(
+            sync_mint_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut Account::default(), &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut create_account_for_test(&Clock::default())],
+        ).unwrap();
+
+        // lengths past the end of their field are rejected as well
+        mint_ext_account.data[1 + 106] = MAX_SYMBOL_NAME_LEN as u8 + 1;
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                sync_mint_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut Account::default(), &mut mint_ext_account, &mut Account::default(), &mut rent_sysvar(), &mut create_account_for_test(&Clock::default())],
+            )
+        );
+    }
+
+    #[test]
+    fn test_migrate_extension() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account_with_layout(&program_id, &mint_key, &pay_key, MintExtensionLayout::V0);
+        let (old_mint_ext, _) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        let old_lamports = mint_ext_account.lamports;
+        let mut pay_account = Account::new(mintext_minimum_balance() + symbol_index_minimum_balance(), 0, &system_program::id());
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        assert_eq!(
+            MintExtensionLayout::detect(&mint_ext_account.data).unwrap(),
+            MintExtensionLayout::V0
+        );
+        do_process_instruction(
+            migrate_extension_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+
+        assert_eq!(mint_ext_account.data.len(), MintExtension::account_len(MintExtensionLayout::LATEST));
+        assert_eq!(mint_ext_account.data[0], AccountType::MintExtensionV3 as u8);
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext, old_mint_ext);
+        // the payer tops up rent for the larger account
+        let top_up = mintext_minimum_balance() - old_lamports;
+        assert!(top_up > 0);
+        assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+        assert_eq!(pay_account.lamports, mintext_minimum_balance() + symbol_index_minimum_balance() - top_up);
+
+        // migrating again changes nothing
+        let pay_lamports = pay_account.lamports;
+        do_process_instruction(
+            migrate_extension_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(pay_account.lamports, pay_lamports);
+        assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0, old_mint_ext);
+
+        // names shortened by earlier versions of ModifyMint lose their stale tail
+        let mut dirty_ext = old_mint_ext;
+        dirty_ext.name = padded("SYM and the rest");
+        let mut dirty_account = Account::new(mint_ext_account.lamports, MintExtension::V2_LEN, &program_id);
+        dirty_ext.pack_account(MintExtensionLayout::V2, &mut dirty_account.data).unwrap();
+        do_process_instruction(
+            migrate_extension_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut dirty_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(MintExtensionView::new(&dirty_account.data[..]).unwrap().check_invariants(), Ok(()));
+        assert_eq!(MintExtension::unpack_account(&dirty_account.data).unwrap().0, old_mint_ext);
+
+        // the migrated extension can still be modified
+        do_process_instruction(
+            modify_mint_instruction(
+                &program_id,
+                &ValidationPolicy::default(),
+                &mint_key,
+                "SYM",
+                String::from("SYM2"),
+                String::from("name of mint"),
+                &pay_key,
+                &[],
+            ).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext.symbol[0..4], *("SYM2".as_bytes()));
+
+        // metadata of V1 accounts moves along
+        let metadata = MintMetadata {
+            tags: vec![String::from("wrapped")],
+            ..MintMetadata::default()
+        };
+        let mut mint_ext_account = new_mint_ext_account_with_layout(&program_id, &mint_key, &pay_key, MintExtensionLayout::V1);
+        mint_ext_account.data.extend_from_slice(&borsh::to_vec(&metadata).unwrap());
+        do_process_instruction(
+            migrate_extension_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(mint_ext_account.data.len(), metadata.account_len());
+        assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap(), (old_mint_ext, MintExtensionLayout::LATEST));
+        assert_eq!(MintMetadata::unpack_account(&mint_ext_account.data).unwrap(), metadata);
+    }
+
+    #[test]
+    fn test_legacy_extension_address() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut symbol_index_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // extensions written by the first versions of the program live at
+        // keypair addresses, which only CloseMint and MigrateExtension take
+        let legacy_key = Pubkey::new_unique();
+        let mut legacy_account = new_mint_ext_account_with_layout(&program_id, &mint_key, &pay_key, MintExtensionLayout::V0);
+        let mut instruction = modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap();
+        instruction.accounts[3].pubkey = legacy_key;
+        assert_eq!(
+            Err(RegistryError::InvalidMintExtensionAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut legacy_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+
+        // and only for the mint they record
+        let other_mint_key = Pubkey::new_unique();
+        let mut other_mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut instruction = migrate_extension_instruction(&program_id, &other_mint_key, &pay_key).unwrap();
+        instruction.accounts[3].pubkey = legacy_key;
+        assert_eq!(
+            Err(RegistryError::InvalidMintExtensionAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut other_mint_account, &mut pay_account, &mut legacy_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+
+        // the mint may be registered again at its derived address meanwhile
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut symbol_index_account],
+        ).unwrap();
+
+        let mut instruction = migrate_extension_instruction(&program_id, &mint_key, &pay_key).unwrap();
+        instruction.accounts[3].pubkey = legacy_key;
+        do_process_instruction(
+            instruction,
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut legacy_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(MintExtensionLayout::detect(&legacy_account.data).unwrap(), MintExtensionLayout::LATEST);
+
+        // closing refunds the rent and leaves the symbol to the registration
+        // at the derived address
+        let dest_key = Pubkey::new_unique();
+        let mut dest_account = Account::default();
+        let symbol_index_before = symbol_index_account.clone();
+        let mut instruction = close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &dest_key, &pay_key, &[]).unwrap();
+        instruction.accounts[3].pubkey = legacy_key;
+        do_process_instruction(
+            instruction,
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut legacy_account, &mut symbol_index_account, &mut dest_account],
+        ).unwrap();
+        assert_eq!(legacy_account.lamports, 0);
+        assert_eq!(legacy_account.owner, system_program::id());
+        assert_eq!(dest_account.lamports, mintext_minimum_balance());
+        assert_eq!(symbol_index_account, symbol_index_before);
+        assert!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.is_initialized);
+    }
+
+    #[test]
+    fn test_update_metadata() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+        let pay_lamports = 1_000_000_000;
+        let mut pay_account = Account::new(pay_lamports, 0, &system_program::id());
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let (old_mint_ext, _) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+
+        let website = String::from("https://example.com");
+        do_process_instruction(
+            set_metadata_field_instruction(&program_id, &mint_key, MetadataField::Website, website.clone(), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        let tags = vec![String::from("stablecoin"), String::from("wrapped")];
+        do_process_instruction(
+            set_tags_instruction(&program_id, &mint_key, tags.clone(), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+
+        let expected = MintMetadata {
+            website,
+            tags,
+            ..MintMetadata::default()
+        };
+        assert_eq!(MintMetadata::unpack_account(&mint_ext_account.data).unwrap(), expected);
+        assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0, old_mint_ext);
+        // the account grew and the authority paid the rent
+        assert_eq!(mint_ext_account.data.len(), expected.account_len());
+        let grown_balance = Rent::default().minimum_balance(expected.account_len());
+        assert_eq!(mint_ext_account.lamports, grown_balance);
+        assert_eq!(pay_account.lamports, pay_lamports - (grown_balance - mintext_minimum_balance()));
+
+        // limits
+        let too_long = "x".repeat(MAX_WEBSITE_LEN + 1);
+        assert_eq!(
+            Err(RegistryError::MetadataTooLong.into()),
+            do_process_instruction(
+                set_metadata_field_instruction(&program_id, &mint_key, MetadataField::Website, too_long, &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::TooManyTags.into()),
+            do_process_instruction(
+                set_tags_instruction(&program_id, &mint_key, vec![String::from("tag"); MAX_TAGS + 1], &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+        for tag in &[String::new(), "x".repeat(MAX_TAG_LEN + 1)] {
+            assert_eq!(
+                Err(RegistryError::InvalidTag.into()),
+                do_process_instruction(
+                    set_tags_instruction(&program_id, &mint_key, vec![tag.clone()], &pay_key, &[]).unwrap(),
+                    vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+                )
+            );
+        }
+
+        // only the mint authority may update
+        let other_key = Pubkey::new_unique();
+        let mut other_account = Account::new(pay_lamports, 0, &system_program::id());
+        assert_eq!(
+            Err(RegistryError::NoAuthority.into()),
+            do_process_instruction(
+                set_tags_instruction(&program_id, &mint_key, vec![], &other_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut other_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+
+        // clearing everything shrinks the account back and refunds the rent
+        do_process_instruction(
+            clear_metadata_field_instruction(&program_id, &mint_key, MetadataField::Website, &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        do_process_instruction(
+            set_tags_instruction(&program_id, &mint_key, vec![], &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(MintMetadata::unpack_account(&mint_ext_account.data).unwrap(), MintMetadata::default());
+        assert_eq!(mint_ext_account.data.len(), MintExtension::account_len(MintExtensionLayout::LATEST));
+        assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+        assert_eq!(pay_account.lamports, pay_lamports);
+    }
+
+    #[test]
+    fn test_update_metadata_migrates() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account_with_layout(&program_id, &mint_key, &pay_key, MintExtensionLayout::V0);
+        let (old_mint_ext, _) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        let logo_uri = String::from("https://example.com/logo.png");
+        do_process_instruction(
+            set_metadata_field_instruction(&program_id, &mint_key, MetadataField::LogoUri, logo_uri.clone(), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext, old_mint_ext);
+        let metadata = MintMetadata::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(metadata.logo_uri, logo_uri);
+        assert_eq!(mint_ext_account.lamports, Rent::default().minimum_balance(metadata.account_len()));
+    }
+
+    #[test]
+    fn test_long_symbol_name() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // 16 bytes used to be rejected
+        let symbol = String::from("SIXTEEN_BYTES_OK");
+        let name = String::from("Wrapped Ethereum (Wormhole)");
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, symbol.clone(), name.clone(), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext.symbol().unwrap(), symbol);
+        assert_eq!(mint_ext.name().unwrap(), name);
+
+        // limits are in bytes, multi-byte characters included
+        let name = "\u{e9}".repeat(MAX_SYMBOL_NAME_LEN / 2);
+        do_process_instruction(
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, &symbol, symbol.clone(), name.clone(), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+        assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.name().unwrap(), name);
+        assert_eq!(
+            Err(RegistryError::SymbolToLong.into()),
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, &symbol, symbol.clone(), name + "x", &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::SymbolToLong.into()),
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, &symbol, "S".repeat(MAX_SYMBOL_NAME_LEN + 1), String::new(), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+
+        // legacy accounts are migrated before the longer values are written,
+        // the payer funding the extra rent
+        let long_name = String::from("Twenty bytes of name");
+        assert_eq!(long_name.len(), 20);
+        for &layout in &[MintExtensionLayout::V0, MintExtensionLayout::V1] {
+            let mut mint_ext_account = new_mint_ext_account_with_layout(&program_id, &mint_key, &pay_key, layout);
+            let pay_lamports = pay_account.lamports;
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", symbol.clone(), long_name.clone(), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            ).unwrap();
+            let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+            assert_eq!(layout, MintExtensionLayout::LATEST);
+            assert_eq!(mint_ext.symbol().unwrap(), symbol);
+            assert_eq!(mint_ext.name().unwrap(), long_name);
+            assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+            assert!(pay_account.lamports < pay_lamports);
+        }
+    }
+
+    #[test]
+    fn test_symbol_name_policy() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        for (symbol, error) in [
+            ("USDC ", RegistryError::UnexpectedWhitespace),
+            ("US\u{0}DC", RegistryError::InvalidCharacter),
+            ("sol", RegistryError::ReservedSymbol),
+            ("", RegistryError::EmptySymbol),
+        ] {
+            assert_eq!(
+                Err(error.into()),
+                do_process_instruction(
+                    register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from(symbol), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+                    vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+                )
+            );
+        }
+
+        // names are trimmed before they are stored
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("USDC"), String::from(" USD Coin  "), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.name().unwrap(), "USD Coin");
+
+        assert_eq!(
+            Err(RegistryError::ReservedSymbol.into()),
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "USDC", String::from("WSOL"), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::UnexpectedWhitespace.into()),
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "USDC", String::from("USDC"), String::from("USD  Coin"), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+    }
+
+    #[test]
+    fn test_symbol_index() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mint_key = Pubkey::new_unique();
+        let other_mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut other_mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut other_mint_ext_account = Account::default();
+        let mut usdc_index_account = Account::default();
+        let mut usdx_index_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("USDC"), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdc_index_account],
+        ).unwrap();
+        assert_eq!(usdc_index_account.owner, program_id);
+        assert_eq!(SymbolIndex::unpack_account(&usdc_index_account.data).unwrap().mint, mint_key);
+
+        // the symbol is taken whatever its case
+        assert_eq!(
+            find_symbol_index_address(&program_id, &ValidationPolicy::default(), "usdc"),
+            find_symbol_index_address(&program_id, &ValidationPolicy::default(), "USDC")
+        );
+        assert_eq!(
+            Err(RegistryError::SymbolTaken.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &other_mint_key, String::from("usdc"), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut other_mint_account, &mut pay_account, &mut other_mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdc_index_account],
+            )
+        );
+
+        // the index must be the one derived from the symbol
+        let mut instruction = register_mint_instruction(&program_id, &ValidationPolicy::default(), &other_mint_key, String::from("OTHER"), String::from("Other"), &pay_key, &[]).unwrap();
+        instruction.accounts[6].pubkey = find_symbol_index_address(&program_id, &ValidationPolicy::default(), "USDX").0;
+        assert_eq!(
+            Err(RegistryError::InvalidSymbolIndexAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut other_mint_account, &mut pay_account, &mut other_mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdx_index_account],
+            )
+        );
+
+        // passing the new index in place of the old one cannot skip the release
+        let usdc_index_before = usdc_index_account.clone();
+        let mut instruction = modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "USDC", String::from("USDX"), String::from("USD Coin"), &pay_key, &[]).unwrap();
+        instruction.accounts[6].pubkey = instruction.accounts[7].pubkey;
+        assert_eq!(
+            Err(RegistryError::InvalidSymbolIndexAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdx_index_account.clone(), &mut usdx_index_account],
+            )
+        );
+        let mut instruction = update_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "USDC", MintUpdate::default().symbol(String::from("USDX")), &pay_key, &[]).unwrap();
+        instruction.accounts[6].pubkey = instruction.accounts[7].pubkey;
+        assert_eq!(
+            Err(RegistryError::InvalidSymbolIndexAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdx_index_account.clone(), &mut usdx_index_account],
+            )
+        );
+        assert_eq!(usdc_index_account, usdc_index_before);
+        assert_eq!(usdx_index_account, Account::default());
+
+        // renaming releases the old symbol and claims the new one
+        let pay_lamports = pay_account.lamports;
+        do_process_instruction(
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "USDC", String::from("USDX"), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdc_index_account, &mut usdx_index_account],
+        ).unwrap();
+        assert_eq!(usdc_index_account.lamports, 0);
+        assert_eq!(usdc_index_account.owner, system_program::id());
+        assert!(usdc_index_account.data.is_empty());
+        assert_eq!(SymbolIndex::unpack_account(&usdx_index_account.data).unwrap().mint, mint_key);
+        assert_eq!(pay_account.lamports, pay_lamports);
+
+        // another mint cannot take the new symbol, but may take the old one
+        assert_eq!(
+            Err(RegistryError::SymbolTaken.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &other_mint_key, String::from("USDX"), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut other_mint_account, &mut pay_account, &mut other_mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdx_index_account],
+            )
+        );
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &other_mint_key, String::from("usdc"), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut other_mint_account, &mut pay_account, &mut other_mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdc_index_account],
+        ).unwrap();
+        assert_eq!(SymbolIndex::unpack_account(&usdc_index_account.data).unwrap().mint, other_mint_key);
+
+        // closing releases the symbol
+        do_process_instruction(
+            close_mint_instruction(&program_id, &ValidationPolicy::default(), &other_mint_key, "usdc", &pay_key, &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut other_mint_account, &mut pay_account.clone(), &mut other_mint_ext_account, &mut usdc_index_account, &mut pay_account],
+        ).unwrap();
+        assert_eq!(usdc_index_account.lamports, 0);
+        assert_eq!(usdc_index_account.owner, system_program::id());
+    }
+
+    #[test]
+    fn test_trimmed_symbol_index() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let other_mint_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut other_mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut other_mint_ext_account = Account::default();
+        let mut usdc_index_account = Account::default();
+        let mut usdce_index_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let policy = ValidationPolicy {
+            symbol_whitespace: Whitespace::Trim,
+            ..ValidationPolicy::default()
+        };
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            policy: policy.clone(),
+            ..RegistryConfig::default()
+        });
+
+        // the builder derives the index of the trimmed symbol the program stores
+        let instruction = register_mint_instruction(&program_id, &policy, &mint_key, String::from(" USDC "), String::from("USD Coin"), &pay_key, &[]).unwrap();
+        assert_eq!(instruction.accounts[6].pubkey, find_symbol_index_address(&program_id, &policy, "USDC").0);
+        do_process_instruction(
+            instruction,
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdc_index_account],
+        ).unwrap();
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.symbol(), Ok("USDC"));
+        assert_eq!(SymbolIndex::unpack_account(&usdc_index_account.data).unwrap().mint, mint_key);
+
+        // and so do the builders renaming it
+        do_process_instruction(
+            modify_mint_instruction(&program_id, &policy, &mint_key, "USDC", String::from(" usdc.e "), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut usdc_index_account, &mut usdce_index_account],
+        ).unwrap();
+        assert_eq!(usdc_index_account.owner, system_program::id());
+        assert_eq!(SymbolIndex::unpack_account(&usdce_index_account.data).unwrap().mint, mint_key);
+
+        // the index of the untrimmed symbol is not the one the program claims
+        assert_eq!(
+            Err(RegistryError::InvalidSymbolIndexAddress.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &other_mint_key, String::from(" USDT "), String::from("Tether"), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut other_mint_account, &mut pay_account, &mut other_mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+    }
+
+    fn new_program_data_account(program_id: &Pubkey, upgrade_authority: &Pubkey) -> (Pubkey, Account) {
+        let (program_data_key, _) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+        // bincode encoding of UpgradeableLoaderState::ProgramData
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(upgrade_authority.as_ref());
+        let mut program_data_account = Account::new(1, data.len(), &bpf_loader_upgradeable::id());
+        program_data_account.data = data;
+        (program_data_key, program_data_account)
+    }
+
+    #[test]
+    fn test_initialize_registry() {
+        let program_id = Pubkey::new_unique();
+        let upgrade_authority_key = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut upgrade_authority_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let (_, mut program_data_account) = new_program_data_account(&program_id, &upgrade_authority_key);
+        let mut config_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // nobody but the upgrade authority may initialize the registry
+        let other_key = Pubkey::new_unique();
+        assert_eq!(
+            Err(RegistryError::NotUpgradeAuthority.into()),
+            do_process_instruction(
+                initialize_registry_instruction(&program_id, &other_key, &admin_key, 5000).unwrap(),
+                vec![&mut config_account, &mut upgrade_authority_account, &mut program_data_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+        let mut forged_program_data_account = program_data_account.clone();
+        forged_program_data_account.owner = program_id;
+        assert_eq!(
+            Err(RegistryError::NotUpgradeAuthority.into()),
+            do_process_instruction(
+                initialize_registry_instruction(&program_id, &upgrade_authority_key, &admin_key, 5000).unwrap(),
+                vec![&mut config_account, &mut upgrade_authority_account, &mut forged_program_data_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+
+        do_process_instruction(
+            initialize_registry_instruction(&program_id, &upgrade_authority_key, &admin_key, 5000).unwrap(),
+            vec![&mut config_account, &mut upgrade_authority_account, &mut program_data_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(config_account.owner, program_id);
+        assert_eq!(config_account.lamports, Rent::default().minimum_balance(RegistryConfig::default().account_len()));
+        assert_eq!(
+            RegistryConfig::unpack_account(&config_account.data).unwrap(),
+            RegistryConfig {
+                admin: admin_key,
+                pending_admin: Pubkey::default(),
+                paused: false,
+                registration_fee: 5000,
+                curators: vec![],
+                policy: ValidationPolicy::default(),
+            }
+        );
+
+        // only once
+        assert_eq!(
+            Err(RegistryError::RegistryAlreadyInitialized.into()),
+            do_process_instruction(
+                initialize_registry_instruction(&program_id, &upgrade_authority_key, &admin_key, 0).unwrap(),
+                vec![&mut config_account, &mut upgrade_authority_account, &mut program_data_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+
+        // other instructions need the config
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        assert_eq!(
+            Err(RegistryError::RegistryNotInitialized.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap(),
+                vec![&mut Account::default(), &mut mint_account, &mut pay_account, &mut Account::default(), &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+        let mut instruction = register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap();
+        instruction.accounts[0].pubkey = Pubkey::new_unique();
+        assert_eq!(
+            Err(RegistryError::InvalidRegistryConfigAddress.into()),
+            do_process_instruction(
+                instruction,
+                vec![&mut config_account, &mut mint_account, &mut pay_account, &mut Account::default(), &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+    }
+
+    #[test]
+    fn test_registry_paused() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut admin_account = Account::default();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account(&program_id, &mint_key, &pay_key);
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            admin: admin_key,
+            ..RegistryConfig::default()
+        });
+
+        assert_eq!(
+            Err(RegistryError::NotAdmin.into()),
+            do_process_instruction(
+                set_paused_instruction(&program_id, &pay_key, true).unwrap(),
+                vec![&mut config_account, &mut pay_account],
+            )
+        );
+        do_process_instruction(
+            set_paused_instruction(&program_id, &admin_key, true).unwrap(),
+            vec![&mut config_account, &mut admin_account],
+        ).unwrap();
+        assert!(RegistryConfig::unpack_account(&config_account.data).unwrap().paused);
+
+        assert_eq!(
+            Err(RegistryError::RegistryPaused.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut pay_account, &mut Account::default(), &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::RegistryPaused.into()),
+            do_process_instruction(
+                close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &pay_key, &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut pay_account.clone(), &mut mint_ext_account, &mut Account::default(), &mut pay_account],
+            )
+        );
+
+        // admin instructions still go through
+        do_process_instruction(
+            set_paused_instruction(&program_id, &admin_key, false).unwrap(),
+            vec![&mut config_account, &mut admin_account],
+        ).unwrap();
+        do_process_instruction(
+            close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &pay_key, &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account.clone(), &mut mint_ext_account, &mut Account::default(), &mut pay_account],
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_registration_fee() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let treasury_key = Pubkey::new_unique();
+        let fee = 1_000_000;
+        let mut admin_account = Account::default();
+        let mut treasury_account = Account::default();
+        let mut pay_account = Account::new(
+            mintext_minimum_balance() + symbol_index_minimum_balance() + fee,
+            0,
+            &system_program::id(),
+        );
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            admin: admin_key,
+            ..RegistryConfig::default()
+        });
+
+        do_process_instruction(
+            set_registration_fee_instruction(&program_id, &admin_key, fee).unwrap(),
+            vec![&mut config_account, &mut admin_account],
+        ).unwrap();
+        let config_lamports = config_account.lamports;
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+        assert_eq!(pay_account.lamports, 0);
+        assert_eq!(config_account.lamports, config_lamports + fee);
+
+        assert_eq!(
+            Err(RegistryError::NotAdmin.into()),
+            do_process_instruction(
+                withdraw_fees_instruction(&program_id, &pay_key, &treasury_key).unwrap(),
+                vec![&mut config_account, &mut pay_account, &mut treasury_account, &mut rent_sysvar],
+            )
+        );
+        do_process_instruction(
+            withdraw_fees_instruction(&program_id, &admin_key, &treasury_key).unwrap(),
+            vec![&mut config_account, &mut admin_account, &mut treasury_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(treasury_account.lamports, fee);
+        assert_eq!(config_account.lamports, config_lamports);
+    }
+
+    #[test]
+    fn test_admin_transfer() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let new_admin_key = Pubkey::new_unique();
+        let mut admin_account = Account::default();
+        let mut new_admin_account = Account::default();
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            admin: admin_key,
+            ..RegistryConfig::default()
+        });
+
+        // nothing to accept yet
+        assert_eq!(
+            Err(RegistryError::NotPendingAdmin.into()),
+            do_process_instruction(
+                accept_admin_instruction(&program_id, &new_admin_key).unwrap(),
+                vec![&mut config_account, &mut new_admin_account],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::NotAdmin.into()),
+            do_process_instruction(
+                propose_admin_instruction(&program_id, &new_admin_key, &new_admin_key).unwrap(),
+                vec![&mut config_account, &mut new_admin_account],
+            )
+        );
+        do_process_instruction(
+            propose_admin_instruction(&program_id, &admin_key, &new_admin_key).unwrap(),
+            vec![&mut config_account, &mut admin_account],
+        ).unwrap();
+        let config = RegistryConfig::unpack_account(&config_account.data).unwrap();
+        assert_eq!(config.admin, admin_key);
+        assert_eq!(config.pending_admin, new_admin_key);
+
+        // only the proposed admin can accept
+        assert_eq!(
+            Err(RegistryError::NotPendingAdmin.into()),
+            do_process_instruction(
+                accept_admin_instruction(&program_id, &admin_key).unwrap(),
+                vec![&mut config_account, &mut admin_account],
+            )
+        );
+        do_process_instruction(
+            accept_admin_instruction(&program_id, &new_admin_key).unwrap(),
+            vec![&mut config_account, &mut new_admin_account],
+        ).unwrap();
+        let config = RegistryConfig::unpack_account(&config_account.data).unwrap();
+        assert_eq!(config.admin, new_admin_key);
+        assert_eq!(config.pending_admin, Pubkey::default());
+
+        // the former admin lost its rights
+        assert_eq!(
+            Err(RegistryError::NotAdmin.into()),
+            do_process_instruction(
+                set_paused_instruction(&program_id, &admin_key, true).unwrap(),
+                vec![&mut config_account, &mut admin_account],
+            )
+        );
+    }
+
+    #[test]
+    fn test_curators() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            admin: admin_key,
+            ..RegistryConfig::default()
+        });
+        let curator_key = Pubkey::new_unique();
+
+        assert_eq!(
+            Err(RegistryError::NotAdmin.into()),
+            do_process_instruction(
+                add_curator_instruction(&program_id, &curator_key, &curator_key).unwrap(),
+                vec![&mut config_account, &mut Account::default(), &mut system_account, &mut rent_sysvar],
+            )
+        );
+        let admin_lamports = admin_account.lamports;
+        let config_len = config_account.data.len();
+        do_process_instruction(
+            add_curator_instruction(&program_id, &admin_key, &curator_key).unwrap(),
+            vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        // adding a listed curator changes nothing
+        do_process_instruction(
+            add_curator_instruction(&program_id, &admin_key, &curator_key).unwrap(),
+            vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(config_account.data.len(), config_len + 32);
+        assert_eq!(config_account.lamports, Rent::default().minimum_balance(config_len + 32));
+        assert!(RegistryConfig::unpack_account(&config_account.data).unwrap().is_curator(&curator_key));
+
+        for _ in 1..MAX_CURATORS {
+            do_process_instruction(
+                add_curator_instruction(&program_id, &admin_key, &Pubkey::new_unique()).unwrap(),
+                vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+            ).unwrap();
+        }
+        assert_eq!(
+            Err(RegistryError::TooManyCurators.into()),
+            do_process_instruction(
+                add_curator_instruction(&program_id, &admin_key, &Pubkey::new_unique()).unwrap(),
+                vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+
+        // removing every curator refunds the admin
+        let curators = RegistryConfig::unpack_account(&config_account.data).unwrap().curators;
+        for curator in &curators {
+            do_process_instruction(
+                remove_curator_instruction(&program_id, &admin_key, curator).unwrap(),
+                vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+            ).unwrap();
+        }
+        assert_eq!(config_account.data.len(), config_len);
+        assert_eq!(admin_account.lamports, admin_lamports);
+        assert_eq!(
+            Err(RegistryError::NotCurator.into()),
+            do_process_instruction(
+                remove_curator_instruction(&program_id, &admin_key, &curator_key).unwrap(),
+                vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+    }
+
+    #[test]
+    fn test_validation_policy() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut admin_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut symbol_index_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            admin: admin_key,
+            ..RegistryConfig::default()
+        });
+        let mut policy = ValidationPolicy {
+            max_symbol_len: 4,
+            ..ValidationPolicy::default()
+        };
+        policy.reserved_symbols.extend([String::from("USDC"), String::from("USDT")]);
+
+        assert_eq!(
+            Err(RegistryError::NotAdmin.into()),
+            do_process_instruction(
+                set_validation_policy_instruction(&program_id, &pay_key, policy.clone()).unwrap(),
+                vec![&mut config_account, &mut pay_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::InvalidValidationPolicy.into()),
+            do_process_instruction(
+                set_validation_policy_instruction(&program_id, &admin_key, ValidationPolicy {
+                    max_name_len: MAX_SYMBOL_NAME_LEN as u8 + 1,
+                    ..ValidationPolicy::default()
+                }).unwrap(),
+                vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+
+        // the admin pays for the longer reserved list
+        let admin_lamports = admin_account.lamports;
+        let config_lamports = config_account.lamports;
+        do_process_instruction(
+            set_validation_policy_instruction(&program_id, &admin_key, policy.clone()).unwrap(),
+            vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        let config = RegistryConfig::unpack_account(&config_account.data).unwrap();
+        assert_eq!(config.policy, policy);
+        assert_eq!(config_account.data.len(), config.account_len());
+        assert_eq!(config_account.lamports, Rent::default().minimum_balance(config.account_len()));
+        assert_eq!(admin_lamports - admin_account.lamports, config_account.lamports - config_lamports);
+
+        // symbols the default policy accepts are rejected by the one in the config
+        assert_eq!(ValidationPolicy::default().validate_symbol("usdc"), Ok(String::from("usdc")));
+        assert_eq!(
+            Err(RegistryError::ReservedSymbol.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &policy, &mint_key, String::from("usdc"), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut symbol_index_account],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::SymbolToLong.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &policy, &mint_key, String::from("USDCX"), String::from("USD Coin"), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut symbol_index_account],
+            )
+        );
+        do_process_instruction(
+            register_mint_instruction(&program_id, &policy, &mint_key, String::from("WBTC"), String::from("Wrapped Bitcoin"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut symbol_index_account],
+        ).unwrap();
+        assert_eq!(
+            Err(RegistryError::ReservedSymbol.into()),
+            do_process_instruction(
+                update_mint_instruction(&program_id, &policy, &mint_key, "WBTC", MintUpdate::default().symbol(String::from("USDT")), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut symbol_index_account, &mut Account::default()],
+            )
+        );
+
+        // going back to the default policy refunds the admin
+        do_process_instruction(
+            set_validation_policy_instruction(&program_id, &admin_key, ValidationPolicy::default()).unwrap(),
+            vec![&mut config_account, &mut admin_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(admin_account.lamports, admin_lamports);
+        assert_eq!(config_account.lamports, config_lamports);
+    }
+
+    #[test]
+    fn test_verify_mint() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let curator_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut curator_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account_with_layout(&program_id, &mint_key, &pay_key, MintExtensionLayout::V2);
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            curators: vec![curator_key],
+            ..RegistryConfig::default()
+        });
+
+        // only curators, not the mint authority
+        assert_eq!(
+            Err(RegistryError::NotCurator.into()),
+            do_process_instruction(
+                verify_mint_instruction(&program_id, &mint_key, &pay_key).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+        let mut instruction = verify_mint_instruction(&program_id, &mint_key, &curator_key).unwrap();
+        instruction.accounts[2].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                instruction,
+                vec![&mut config_account, &mut mint_account, &mut curator_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+
+        // verifying a V2 extension moves it to the latest layout
+        do_process_instruction(
+            verify_mint_instruction(&program_id, &mint_key, &curator_key).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut curator_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert!(mint_ext.verified);
+        assert_eq!(mint_ext.symbol().unwrap(), "SYM");
+        assert_eq!(mint_ext_account.lamports, mintext_minimum_balance());
+
+        do_process_instruction(
+            unverify_mint_instruction(&program_id, &mint_key, &curator_key).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut curator_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert!(!MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.verified);
+
+        // renaming a verified mint clears the flag
+        do_process_instruction(
+            verify_mint_instruction(&program_id, &mint_key, &curator_key).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut curator_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        do_process_instruction(
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", String::from("SYM"), String::from("new name"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.name().unwrap(), "new name");
+        assert!(!mint_ext.verified);
+
+        // metadata updates keep the flag
+        do_process_instruction(
+            verify_mint_instruction(&program_id, &mint_key, &curator_key).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut curator_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        do_process_instruction(
+            set_metadata_field_instruction(&program_id, &mint_key, MetadataField::Website, String::from("https://example.com"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.verified);
+
+        // closing and registering again starts unverified
+        do_process_instruction(
+            close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &pay_key, &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account.clone(), &mut mint_ext_account, &mut Account::default(), &mut pay_account],
+        ).unwrap();
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+        assert!(!MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.verified);
+    }
+
+    #[test]
+    fn test_events() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let curator_key = Pubkey::new_unique();
+        let update_authority_key = Pubkey::new_unique();
+        let dest_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut curator_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut update_authority_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            curators: vec![curator_key],
+            ..RegistryConfig::default()
+        });
+
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+        assert_eq!(logged_events(), vec![RegistryEvent::Registered {
+            mint: mint_key,
+            symbol: String::from("SYM"),
+            name: String::from("name"),
+            proof: RegistrationProof::MintAuthority,
+            signer: pay_key,
+        }]);
+
+        do_process_instruction(
+            verify_mint_instruction(&program_id, &mint_key, &curator_key).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut curator_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(logged_events(), vec![RegistryEvent::Verified {
+            mint: mint_key,
+            old_verified: false,
+            new_verified: true,
+            signer: curator_key,
+        }]);
+
+        // unchanged fields are logged with their current value
+        do_process_instruction(
+            update_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", MintUpdate::default().name(String::from("new name")), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+        assert_eq!(logged_events(), vec![RegistryEvent::Modified {
+            mint: mint_key,
+            old_symbol: String::from("SYM"),
+            new_symbol: String::from("SYM"),
+            old_name: String::from("name"),
+            new_name: String::from("new name"),
+            signer: pay_key,
+        }]);
+
+        // nor is anything logged for an update changing nothing
+        assert_eq!(
+            Err(RegistryError::InvalidInstruction.into()),
+            do_process_instruction(
+                update_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", MintUpdate::default(), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        assert_eq!(logged_events(), vec![]);
+
+        // metadata changes leave the update authority alone and log nothing
+        do_process_instruction(
+            set_metadata_field_instruction(&program_id, &mint_key, MetadataField::Website, String::from("https://example.com"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(logged_events(), vec![]);
+
+        do_process_instruction(
+            set_update_authority_instruction(&program_id, &mint_key, &update_authority_key, &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(logged_events(), vec![RegistryEvent::AuthorityChanged {
+            mint: mint_key,
+            old_authority: pay_key,
+            new_authority: update_authority_key,
+            signer: pay_key,
+        }]);
+
+        do_process_instruction(
+            close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &dest_key, &update_authority_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut update_authority_account, &mut mint_ext_account, &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+        assert_eq!(logged_events(), vec![RegistryEvent::Closed {
+            mint: mint_key,
+            old_symbol: String::from("SYM"),
+            old_name: String::from("new name"),
+            signer: update_authority_key,
+        }]);
+    }
+
+    #[test]
+    fn test_update_authority() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let update_authority_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut update_authority_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+        assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.update_authority, pay_key);
+
+        do_process_instruction(
+            set_update_authority_instruction(&program_id, &mint_key, &update_authority_key, &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(
+            MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.update_authority,
+            update_authority_key
+        );
+
+        // the supply gets fixed; the update authority keeps control
+        let mut mint_state = Mint::unpack_unchecked(&mint_account.data).unwrap();
+        mint_state.mint_authority = COption::None;
+        mint_state.pack_into_slice(&mut mint_account.data);
+        assert_eq!(
+            Err(RegistryError::NoAuthority.into()),
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", String::from("SYM"), String::from("new name"), &pay_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        do_process_instruction(
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", String::from("SYM"), String::from("new name"), &update_authority_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut update_authority_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+        assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.name().unwrap(), "new name");
+
+        // the default key is not an authority
+        assert_eq!(
+            Err(RegistryError::InvalidInstruction.into()),
+            do_process_instruction(
+                set_update_authority_instruction(&program_id, &mint_key, &Pubkey::default(), &update_authority_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut update_authority_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+            )
+        );
+
+        // once revoked nobody can modify or close the extension
+        do_process_instruction(
+            revoke_update_authority_instruction(&program_id, &mint_key, &update_authority_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut update_authority_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        assert_eq!(
+            Err(RegistryError::NoUpdateAuthority.into()),
+            do_process_instruction(
+                modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", String::from("SYM"), String::from("name"), &update_authority_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut update_authority_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::NoUpdateAuthority.into()),
+            do_process_instruction(
+                close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", &update_authority_key, &update_authority_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut update_authority_account.clone(), &mut mint_ext_account, &mut Account::default(), &mut update_authority_account],
+            )
+        );
+    }
+
+    #[test]
+    fn test_update_authority_legacy_layout() {
+        let program_id = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let new_authority_key = Pubkey::new_unique();
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut mint_account = new_mint_account(&spl_token::id(), &pay_key);
+        let mut mint_ext_account = new_mint_ext_account_with_layout(&program_id, &mint_key, &pay_key, MintExtensionLayout::V1);
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // extensions written before update authorities answer to the
+        // mint authority recorded at registration, which migration keeps
+        do_process_instruction(
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", String::from("SYM"), String::from("name"), &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+        ).unwrap();
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext.update_authority, pay_key);
+
+        // which may hand them over
+        do_process_instruction(
+            set_update_authority_instruction(&program_id, &mint_key, &new_authority_key, &pay_key, &[]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar],
+        ).unwrap();
+        let (mint_ext, layout) = MintExtension::unpack_account(&mint_ext_account.data).unwrap();
+        assert_eq!(layout, MintExtensionLayout::LATEST);
+        assert_eq!(mint_ext.update_authority, new_authority_key);
+        assert_eq!(mint_ext.mint_authority, pay_key);
+    }
+
+    fn new_fixed_supply_mint_account(freeze_authority: COption<Pubkey>) -> Account {
+        let mint_account_state = Mint {
+            mint_authority: COption::None,
+            supply: 21_000_000,
+            decimals: 8,
+            is_initialized: true,
+            freeze_authority,
+        };
+        let mut mint_account = Account::new(mint_minimum_balance(), Mint::get_packed_len(), &spl_token::id());
+        mint_account_state.pack_into_slice(&mut mint_account.data);
+        mint_account
+    }
+
+    #[test]
+    fn test_register_fixed_supply_mint() {
+        let program_id = Pubkey::new_unique();
+        let freeze_authority_key = Pubkey::new_unique();
+        let pay_key = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut freeze_authority_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut pay_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let mut admin_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+        let mut config_account = new_registry_config_account_with(&program_id, RegistryConfig {
+            admin: admin_key,
+            ..RegistryConfig::default()
+        });
+
+        // the mint authority is gone
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_fixed_supply_mint_account(COption::Some(freeze_authority_key));
+        let mut mint_ext_account = Account::default();
+        assert_eq!(
+            Err(RegistryError::NoMintAuthority.into()),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("FIX"), String::from("Fixed"), &freeze_authority_key, &[]).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut freeze_authority_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+
+        // the freeze authority proves control instead
+        assert_eq!(
+            Err(RegistryError::NoAuthority.into()),
+            do_process_instruction(
+                register_mint_with_freeze_authority_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("FIX"), String::from("Fixed"), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut mint_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+        do_process_instruction(
+            register_mint_with_freeze_authority_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("FIX"), String::from("Fixed"), &freeze_authority_key, &[]).unwrap(),
+            vec![&mut config_account, &mut mint_account, &mut freeze_authority_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.registration_proof, RegistrationProof::FreezeAuthority);
+        assert_eq!(mint_ext.update_authority, freeze_authority_key);
+        assert_eq!(mint_ext.mint_authority, Pubkey::default());
+        assert_eq!(mint_ext.supply, 21_000_000);
+
+        // but not while the supply can still change
+        let minting_key = Pubkey::new_unique();
+        let mut minting_account = new_mint_account(&spl_token::id(), &pay_key);
+        assert_eq!(
+            Err(RegistryError::SupplyNotFixed.into()),
+            do_process_instruction(
+                register_mint_with_freeze_authority_instruction(&program_id, &ValidationPolicy::default(), &minting_key, String::from("MNT"), String::from("Minting"), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut minting_account, &mut pay_account, &mut Account::default(), &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+
+        // without a freeze authority only the admin can vouch for the registrant
+        let frozen_key = Pubkey::new_unique();
+        let mut frozen_account = new_fixed_supply_mint_account(COption::None);
+        let mut frozen_ext_account = Account::default();
+        assert_eq!(
+            Err(RegistryError::NoFreezeAuthority.into()),
+            do_process_instruction(
+                register_mint_with_freeze_authority_instruction(&program_id, &ValidationPolicy::default(), &frozen_key, String::from("FRZ"), String::from("Frozen"), &pay_key, &[]).unwrap(),
+                vec![&mut config_account, &mut frozen_account, &mut pay_account, &mut frozen_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+            )
+        );
+        assert_eq!(
+            Err(RegistryError::NotAdmin.into()),
+            do_process_instruction(
+                register_mint_with_attestation_instruction(&program_id, &ValidationPolicy::default(), &frozen_key, String::from("FRZ"), String::from("Frozen"), &pay_key, &pay_key).unwrap(),
+                vec![&mut config_account, &mut frozen_account, &mut pay_account, &mut frozen_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut Account::default()],
+            )
+        );
+        let mut instruction = register_mint_with_attestation_instruction(&program_id, &ValidationPolicy::default(), &frozen_key, String::from("FRZ"), String::from("Frozen"), &pay_key, &admin_key).unwrap();
+        instruction.accounts[7].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                instruction,
+                vec![&mut config_account, &mut frozen_account, &mut pay_account, &mut frozen_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut admin_account],
+            )
+        );
+        do_process_instruction(
+            register_mint_with_attestation_instruction(&program_id, &ValidationPolicy::default(), &frozen_key, String::from("FRZ"), String::from("Frozen"), &pay_key, &admin_key).unwrap(),
+            vec![&mut config_account, &mut frozen_account, &mut pay_account, &mut frozen_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut admin_account],
+        ).unwrap();
+        let mint_ext = MintExtension::unpack_account(&frozen_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.registration_proof, RegistrationProof::AdminAttestation);
+        assert_eq!(mint_ext.update_authority, pay_key);
+        assert_eq!(mint_ext.symbol().unwrap(), "FRZ");
+
+        // the mint authority path is recorded too
+        let mut mint_ext_account = Account::default();
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &minting_key, String::from("MNT"), String::from("Minting"), &pay_key, &[]).unwrap(),
+            vec![&mut config_account, &mut minting_account, &mut pay_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default()],
+        ).unwrap();
+        assert_eq!(
+            MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.registration_proof,
+            RegistrationProof::MintAuthority
+        );
+    }
+
+    fn new_multisig_account(owner: &Pubkey, m: u8, signers: &[Pubkey]) -> Account {
+        let mut multisig = Multisig {
+            m,
+            n: signers.len() as u8,
+            is_initialized: true,
+            ..Multisig::default()
+        };
+        multisig.signers[..signers.len()].copy_from_slice(signers);
+        let mut multisig_account = Account::new(
+            Rent::default().minimum_balance(Multisig::LEN),
+            Multisig::LEN,
+            owner,
+        );
+        Multisig::pack(multisig, &mut multisig_account.data).unwrap();
+        multisig_account
+    }
+
+    #[test]
+    fn test_multisig_authority() {
+        let program_id = Pubkey::new_unique();
+        let signer_keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut signer_accounts = [
+            Account::new(mintext_minimum_balance() + 2 * symbol_index_minimum_balance(), 0, &system_program::id()),
+            Account::new(symbol_index_minimum_balance(), 0, &system_program::id()),
+            Account::default(),
+        ];
+        let multisig_key = Pubkey::new_unique();
+        let mut multisig_account = new_multisig_account(&spl_token::id(), 2, &signer_keys);
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = new_mint_account(&spl_token::id(), &multisig_key);
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // one signer of a 2 of 3 multisig is not enough
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name of mint"), &multisig_key, &[&signer_keys[0]]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut signer_accounts[0].clone()],
+            )
+        );
+        // nor is the same signer twice
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name of mint"), &multisig_key, &[&signer_keys[0], &signer_keys[0]]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut signer_accounts[0].clone(), &mut signer_accounts[0].clone()],
+            )
+        );
+        // every listed signer of the multisig must sign
+        let mut instruction = register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name of mint"), &multisig_key, &[&signer_keys[0], &signer_keys[2]]).unwrap();
+        instruction.accounts[8].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                instruction,
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut signer_accounts[0].clone(), &mut signer_accounts[2].clone()],
+            )
+        );
+
+        // the first signer pays, the multisig becomes the update authority
+        let [first_account, second_account, third_account] = &mut signer_accounts;
+        do_process_instruction(
+            register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name of mint"), &multisig_key, &[&signer_keys[0], &signer_keys[2]]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), first_account, third_account],
+        ).unwrap();
+        assert_eq!(first_account.lamports, symbol_index_minimum_balance());
+        assert_eq!(multisig_account.lamports, Rent::default().minimum_balance(Multisig::LEN));
+        let mint_ext = MintExtension::unpack_account(&mint_ext_account.data).unwrap().0;
+        assert_eq!(mint_ext.update_authority, multisig_key);
+        assert_eq!(mint_ext.mint_authority, multisig_key);
+
+        // signers need not be listed in order
+        let mut symbol_index_account = Account::default();
+        do_process_instruction(
+            modify_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM", String::from("SYM2"), String::from("name of mint"), &multisig_key, &[&signer_keys[1], &signer_keys[0]]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut symbol_index_account, second_account, first_account],
+        ).unwrap();
+        assert_eq!(second_account.lamports, 0);
+        assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.symbol().unwrap(), "SYM2");
+
+        // a plain signature by the multisig address is not accepted
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM2", &signer_keys[2], &multisig_key, &[]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut symbol_index_account, third_account],
+            )
+        );
+        do_process_instruction(
+            close_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, "SYM2", &signer_keys[2], &multisig_key, &[&signer_keys[1], &signer_keys[0]]).unwrap(),
+            vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut symbol_index_account, third_account, second_account, first_account],
+        ).unwrap();
+        assert_eq!(mint_ext_account.lamports, 0);
+        assert_eq!(third_account.lamports, mintext_minimum_balance() + symbol_index_minimum_balance());
+    }
+
+    #[test]
+    fn test_multisig_token_program() {
+        let program_id = Pubkey::new_unique();
+        let signer_keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let signer_account = Account::new(1_000_000_000, 0, &system_program::id());
+        let multisig_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_ext_account = Account::default();
+        let mut system_account = Account::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // a Token-2022 multisig cannot sign for an SPL Token mint
+        let mut multisig_account = new_multisig_account(&spl_token_2022::id(), 2, &signer_keys);
+        let mut mint_account = new_mint_account(&spl_token::id(), &multisig_key);
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name of mint"), &multisig_key, &[&signer_keys[0], &signer_keys[1]]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut signer_account.clone(), &mut signer_account.clone()],
+            )
+        );
+        assert_eq!(mint_ext_account, Account::default());
+
+        // but does for a Token-2022 mint
+        #[cfg(feature = "token-2022")]
+        {
+            let mut mint_account = new_mint_account(&spl_token_2022::id(), &multisig_key);
+            do_process_instruction(
+                register_mint_instruction(&program_id, &ValidationPolicy::default(), &mint_key, String::from("SYM"), String::from("name of mint"), &multisig_key, &[&signer_keys[0], &signer_keys[1]]).unwrap(),
+                vec![&mut new_registry_config_account(&program_id), &mut mint_account, &mut multisig_account, &mut mint_ext_account, &mut system_account, &mut rent_sysvar, &mut Account::default(), &mut signer_account.clone(), &mut Account::default()],
+            ).unwrap();
+            assert_eq!(MintExtension::unpack_account(&mint_ext_account.data).unwrap().0.update_authority, multisig_key);
+        }
+    }
+}
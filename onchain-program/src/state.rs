@@ -3,6 +3,8 @@
 
 
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use crate::validation::ValidationPolicy;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 //use num_enum::TryFromPrimitive;
 use solana_program::{
     program_error::ProgramError,
@@ -12,12 +14,123 @@ use solana_program::{
 };
 
 
-/// maximum length of symbol or name
+/// maximum length of symbol or name, in bytes
 pub const MAX_SYMBOL_NAME_LEN: usize = 32;
+/// room for symbol or name in the V0 and V1 layouts
+pub const LEGACY_SYMBOL_NAME_LEN: usize = 16;
+
+/// maximum length of the logo URI
+pub const MAX_LOGO_URI_LEN: usize = 200;
+/// maximum length of the website
+pub const MAX_WEBSITE_LEN: usize = 128;
+/// maximum length of the description
+pub const MAX_DESCRIPTION_LEN: usize = 255;
+/// maximum number of tags
+pub const MAX_TAGS: usize = 8;
+/// maximum length of a single tag
+pub const MAX_TAG_LEN: usize = 32;
+/// maximum number of curators in the RegistryConfig
+pub const MAX_CURATORS: usize = 16;
+
+/// Tag in the first byte of accounts written by the program, telling the
+/// account type and layout version apart before the rest is decoded.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum AccountType {
+    /// Account data not yet written
+    Uninitialized,
+    /// MintExtension with 16 byte symbol and name, in the packed layout
+    MintExtensionV1,
+    /// MintExtension followed by its Borsh encoding
+    MintExtensionV2,
+    /// SymbolIndex followed by its Borsh encoding
+    SymbolIndex,
+    /// RegistryConfig followed by its Borsh encoding
+    RegistryConfig,
+    /// MintExtension with the registry fields and reserved bytes, followed
+    /// by its Borsh encoding
+    MintExtensionV3,
+}
+
+/// How the registrant proved it controls the mint.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum RegistrationProof {
+    /// signed by the mint authority
+    #[default]
+    MintAuthority,
+    /// signed by the freeze authority of a mint whose supply is fixed
+    FreezeAuthority,
+    /// vouched for by the registry admin
+    AdminAttestation,
+}
+
+/// Layouts a MintExtension account can be stored in, oldest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MintExtensionLayout {
+    /// 140 byte packed layout without tag, written before accounts were versioned
+    V0,
+    /// [AccountType::MintExtensionV1](enum.AccountType.html) tag followed by the V0 layout
+    V1,
+    /// [AccountType::MintExtensionV2](enum.AccountType.html) tag followed by the Borsh
+    /// encoding, with room for [MAX_SYMBOL_NAME_LEN](constant.MAX_SYMBOL_NAME_LEN.html)
+    /// byte symbol and name
+    V2,
+    /// [AccountType::MintExtensionV3](enum.AccountType.html) tag followed by the Borsh
+    /// encoding, with verified, update authority, registration proof and last
+    /// sync slot appended, then [RESERVED_LEN](struct.MintExtension.html#associatedconstant.RESERVED_LEN)
+    /// zero bytes
+    V3,
+}
+impl MintExtensionLayout {
+    /// Layout written by RegisterMint and MigrateExtension
+    pub const LATEST: Self = Self::V3;
+
+    /// Every layout, oldest first
+    pub const ALL: [Self; 4] = [Self::V0, Self::V1, Self::V2, Self::V3];
+
+    /// Tells the layout of MintExtension account data without decoding it.
+    ///
+    /// Untagged accounts are recognised by their size.
+    pub fn detect(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() == MintExtension::LEN {
+            return Ok(Self::V0);
+        }
+        match src.first() {
+            Some(&tag) if tag == AccountType::MintExtensionV1 as u8 => Ok(Self::V1),
+            Some(&tag) if tag == AccountType::MintExtensionV2 as u8 => Ok(Self::V2),
+            Some(&tag) if tag == AccountType::MintExtensionV3 as u8 => Ok(Self::V3),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Tag leading accounts in the layout, none for V0
+    pub fn account_type(self) -> Option<AccountType> {
+        match self {
+            Self::V0 => None,
+            Self::V1 => Some(AccountType::MintExtensionV1),
+            Self::V2 => Some(AccountType::MintExtensionV2),
+            Self::V3 => Some(AccountType::MintExtensionV3),
+        }
+    }
+
+    /// Longest symbol or name the layout can hold
+    pub fn max_symbol_name_len(self) -> usize {
+        match self {
+            Self::V0 | Self::V1 => LEGACY_SYMBOL_NAME_LEN,
+            Self::V2 | Self::V3 => MAX_SYMBOL_NAME_LEN,
+        }
+    }
+}
 
 /// MintExtension data.
+///
+/// Fields added by a new layout go at the end, so that the Borsh encoding
+/// of an older tagged layout is a prefix of the latest one. The V3 layout
+/// ends with reserved bytes, which later fields can take over without
+/// growing the account; as they are zero, such fields read back as zero.
 #[repr(C)]
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct MintExtension {
     /// mint_authority
     pub mint_authority: Pubkey,
@@ -34,18 +147,621 @@ pub struct MintExtension {
     /// symbol_len
     pub symbol_len: u8,
     /// symbol
-    pub symbol: [u8;16],
+    pub symbol: [u8; MAX_SYMBOL_NAME_LEN],
     /// name_len
     pub name_len: u8,
     /// name
-    pub name: [u8;16],
+    pub name: [u8; MAX_SYMBOL_NAME_LEN],
+    /// set by a registry curator, cleared when symbol or name change
+    pub verified: bool,
+    /// authority allowed to modify or close the extension, default once revoked
+    ///
+    /// Layouts predating it give the mint authority recorded at registration.
+    pub update_authority: Pubkey,
+    /// proof of control over the mint given at registration
+    pub registration_proof: RegistrationProof,
+    /// slot of the last SyncMint refreshing the fields read from the mint,
+    /// 0 until the first one
+    pub last_sync_slot: u64,
 }
+impl MintExtension {
+    /// Size of an account in the V1 layout, tag included
+    pub const V1_LEN: usize = 1 + Self::LEN;
+    /// Size of an account in the V2 layout, tag included
+    pub const V2_LEN: usize = 1 + 32 + 32 + 8 + 1 + 1 + 32 + 1 + MAX_SYMBOL_NAME_LEN + 1 + MAX_SYMBOL_NAME_LEN;
+    /// Zero bytes at the end of the V3 layout, kept for later fields
+    pub const RESERVED_LEN: usize = 64;
+    /// Size of an account in the V3 layout, tag included
+    pub const V3_LEN: usize = Self::V2_LEN + 1 + 32 + 1 + 8 + Self::RESERVED_LEN;
+
+    /// Size of an account holding a MintExtension in the given layout
+    pub fn account_len(layout: MintExtensionLayout) -> usize {
+        match layout {
+            MintExtensionLayout::V0 => Self::LEN,
+            MintExtensionLayout::V1 => Self::V1_LEN,
+            MintExtensionLayout::V2 => Self::V2_LEN,
+            MintExtensionLayout::V3 => Self::V3_LEN,
+        }
+    }
+
+    /// Tells whether the layout can hold this extension: symbol and name
+    /// must fit, and fields the layout predates must keep the value they
+    /// are read back with.
+    pub fn fits(&self, layout: MintExtensionLayout) -> bool {
+        let max_len = layout.max_symbol_name_len();
+        if usize::from(self.symbol_len) > max_len || usize::from(self.name_len) > max_len {
+            return false;
+        }
+        layout >= MintExtensionLayout::V3
+            || (!self.verified
+                && self.update_authority == self.mint_authority
+                && self.registration_proof == RegistrationProof::MintAuthority
+                && self.last_sync_slot == 0)
+    }
+
+    /// Symbol as a string, checked to end on a UTF-8 character boundary.
+    pub fn symbol(&self) -> Result<&str, ProgramError> {
+        unpack_str(&self.symbol, self.symbol_len)
+    }
+
+    /// Name as a string, checked to end on a UTF-8 character boundary.
+    pub fn name(&self) -> Result<&str, ProgramError> {
+        unpack_str(&self.name, self.name_len)
+    }
+
+    /// Checks that symbol and name lengths are in range and that the bytes
+    /// past them are zero, so clients reading the whole fields see the same
+    /// values as those honouring the lengths.
+    pub fn check_invariants(&self) -> Result<(), ProgramError> {
+        check_padding(&self.symbol, self.symbol_len)?;
+        check_padding(&self.name, self.name_len)
+    }
+
+    /// Zeroes the symbol and name bytes past their lengths.
+    pub fn clear_padding(&mut self) {
+        let symbol_len = usize::from(self.symbol_len).min(MAX_SYMBOL_NAME_LEN);
+        self.symbol[symbol_len..].fill(0);
+        let name_len = usize::from(self.name_len).min(MAX_SYMBOL_NAME_LEN);
+        self.name[name_len..].fill(0);
+    }
+
+    /// Unpacks account data in any layout, telling which one was found.
+    ///
+    /// In the tagged layouts the [MintMetadata](struct.MintMetadata.html)
+    /// section following the extension is left alone.
+    pub fn unpack_account(src: &[u8]) -> Result<(Self, MintExtensionLayout), ProgramError> {
+        let layout = MintExtensionLayout::detect(src)?;
+        let body = src
+            .get(..Self::account_len(layout))
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let mint_ext = match layout {
+            MintExtensionLayout::V0 => Self::unpack_unchecked(body)?,
+            MintExtensionLayout::V1 => Self::unpack_unchecked(&body[1..])?,
+            MintExtensionLayout::V2 | MintExtensionLayout::V3 => {
+                // fields missing from older layouts decode as zero, and the
+                // reserved bytes are left over
+                let mut latest = vec![0; Self::account_len(MintExtensionLayout::LATEST) - 1];
+                latest[..body.len() - 1].copy_from_slice(&body[1..]);
+                Self::deserialize(&mut &latest[..]).map_err(|_| ProgramError::InvalidAccountData)?
+            }
+        };
+        let mint_ext = if layout < MintExtensionLayout::V3 {
+            Self { update_authority: mint_ext.mint_authority, ..mint_ext }
+        } else {
+            mint_ext
+        };
+        Ok((mint_ext, layout))
+    }
+
+    /// Packs into account data using the given layout, leaving any metadata
+    /// section in place.
+    ///
+    /// Fails with AccountDataTooSmall when the extension does not
+    /// [fit](#method.fits) the layout; such accounts have to be migrated first.
+    pub fn pack_account(&self, layout: MintExtensionLayout, dst: &mut [u8]) -> Result<(), ProgramError> {
+        let len = Self::account_len(layout);
+        if dst.len() < len || (layout == MintExtensionLayout::V0 && dst.len() != len) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if !self.fits(layout) {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        if let Some(account_type) = layout.account_type() {
+            dst[0] = account_type as u8;
+        }
+        match layout {
+            MintExtensionLayout::V0 => self.pack_into_slice(dst),
+            MintExtensionLayout::V1 => self.pack_into_slice(&mut dst[1..len]),
+            MintExtensionLayout::V2 | MintExtensionLayout::V3 => {
+                let latest = borsh::to_vec(self).map_err(|_| ProgramError::AccountDataTooSmall)?;
+                let (fields, reserved) = dst[1..len].split_at_mut(latest.len().min(len - 1));
+                fields.copy_from_slice(&latest[..fields.len()]);
+                reserved.fill(0);
+            }
+        }
+        Ok(())
+    }
+}
+
+// offsets of the MintExtension fields from the start of the extension,
+// the same in the packed and Borsh layouts up to the symbol
+const MINT_AUTHORITY_OFFSET: usize = 0;
+const FREEZE_AUTHORITY_OFFSET: usize = 32;
+const SUPPLY_OFFSET: usize = 64;
+const DECIMALS_OFFSET: usize = 72;
+const IS_INITIALIZED_OFFSET: usize = 73;
+const MINT_OFFSET: usize = 74;
+const SYMBOL_LEN_OFFSET: usize = 106;
+
+/// MintExtension read and written in place in the account data, without
+/// decoding the whole extension.
+///
+/// Works on every layout. Fields a layout predates read back as
+/// [unpack_account](struct.MintExtension.html#method.unpack_account) gives
+/// them, and setting them to anything else fails with AccountDataTooSmall,
+/// like [pack_account](struct.MintExtension.html#method.pack_account).
+#[derive(Debug)]
+pub struct MintExtensionView<D> {
+    data: D,
+    layout: MintExtensionLayout,
+}
+impl<D: AsRef<[u8]>> MintExtensionView<D> {
+    /// Wraps account data in any layout, rejecting what
+    /// [unpack_account](struct.MintExtension.html#method.unpack_account) rejects.
+    pub fn new(data: D) -> Result<Self, ProgramError> {
+        let layout = MintExtensionLayout::detect(data.as_ref())?;
+        if data.as_ref().len() < MintExtension::account_len(layout) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let view = Self { data, layout };
+        view.check_bool(IS_INITIALIZED_OFFSET)?;
+        if layout >= MintExtensionLayout::V3 {
+            view.check_bool(view.verified_offset())?;
+            if view.byte(view.registration_proof_offset()) > 2 {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+        Ok(view)
+    }
+
+    /// Layout of the account data
+    pub fn layout(&self) -> MintExtensionLayout {
+        self.layout
+    }
+
+    /// mint_authority
+    pub fn mint_authority(&self) -> Pubkey {
+        self.pubkey(MINT_AUTHORITY_OFFSET)
+    }
+
+    /// freeze_authority
+    pub fn freeze_authority(&self) -> Pubkey {
+        self.pubkey(FREEZE_AUTHORITY_OFFSET)
+    }
+
+    /// supply
+    pub fn supply(&self) -> u64 {
+        u64::from_le_bytes(*array_ref![self.body(), SUPPLY_OFFSET, 8])
+    }
+
+    /// decimals
+    pub fn decimals(&self) -> u8 {
+        self.byte(DECIMALS_OFFSET)
+    }
+
+    /// is_initialized
+    pub fn is_initialized(&self) -> bool {
+        self.byte(IS_INITIALIZED_OFFSET) == 1
+    }
+
+    /// mint
+    pub fn mint(&self) -> Pubkey {
+        self.pubkey(MINT_OFFSET)
+    }
+
+    /// Symbol as a string, checked to end on a UTF-8 character boundary.
+    pub fn symbol(&self) -> Result<&str, ProgramError> {
+        self.str_field(SYMBOL_LEN_OFFSET)
+    }
+
+    /// Name as a string, checked to end on a UTF-8 character boundary.
+    pub fn name(&self) -> Result<&str, ProgramError> {
+        self.str_field(self.name_len_offset())
+    }
+
+    /// Checks the account data as
+    /// [MintExtension::check_invariants](struct.MintExtension.html#method.check_invariants)
+    /// does, against the room the layout has for symbol and name.
+    pub fn check_invariants(&self) -> Result<(), ProgramError> {
+        let max_len = self.layout.max_symbol_name_len();
+        for len_offset in [SYMBOL_LEN_OFFSET, self.name_len_offset()] {
+            check_padding(&self.body()[len_offset + 1..len_offset + 1 + max_len], self.byte(len_offset))?;
+        }
+        Ok(())
+    }
+
+    /// verified, false in layouts before V3
+    pub fn verified(&self) -> bool {
+        self.layout >= MintExtensionLayout::V3 && self.byte(self.verified_offset()) == 1
+    }
+
+    /// update_authority, the mint authority in layouts before V3
+    pub fn update_authority(&self) -> Pubkey {
+        if self.layout < MintExtensionLayout::V3 {
+            return self.mint_authority();
+        }
+        self.pubkey(self.update_authority_offset())
+    }
+
+    /// registration_proof, MintAuthority in layouts before V3
+    pub fn registration_proof(&self) -> RegistrationProof {
+        if self.layout < MintExtensionLayout::V3 {
+            return RegistrationProof::MintAuthority;
+        }
+        match self.byte(self.registration_proof_offset()) {
+            1 => RegistrationProof::FreezeAuthority,
+            2 => RegistrationProof::AdminAttestation,
+            _ => RegistrationProof::MintAuthority,
+        }
+    }
+
+    /// last_sync_slot, 0 in layouts before V3
+    pub fn last_sync_slot(&self) -> u64 {
+        if self.layout < MintExtensionLayout::V3 {
+            return 0;
+        }
+        u64::from_le_bytes(*array_ref![self.body(), self.last_sync_slot_offset(), 8])
+    }
+
+    fn body(&self) -> &[u8] {
+        let data = self.data.as_ref();
+        match self.layout {
+            MintExtensionLayout::V0 => data,
+            _ => &data[1..],
+        }
+    }
+
+    fn byte(&self, offset: usize) -> u8 {
+        self.body()[offset]
+    }
+
+    fn pubkey(&self, offset: usize) -> Pubkey {
+        Pubkey::new_from_array(*array_ref![self.body(), offset, 32])
+    }
+
+    fn str_field(&self, len_offset: usize) -> Result<&str, ProgramError> {
+        let max_len = self.layout.max_symbol_name_len();
+        let len = self.byte(len_offset);
+        if usize::from(len) > max_len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        unpack_str(&self.body()[len_offset + 1..len_offset + 1 + max_len], len)
+    }
+
+    fn check_bool(&self, offset: usize) -> Result<(), ProgramError> {
+        match self.byte(offset) {
+            0 | 1 => Ok(()),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    fn name_len_offset(&self) -> usize {
+        SYMBOL_LEN_OFFSET + 1 + self.layout.max_symbol_name_len()
+    }
+
+    fn verified_offset(&self) -> usize {
+        self.name_len_offset() + 1 + self.layout.max_symbol_name_len()
+    }
+
+    fn update_authority_offset(&self) -> usize {
+        self.verified_offset() + 1
+    }
+
+    fn registration_proof_offset(&self) -> usize {
+        self.update_authority_offset() + 32
+    }
+
+    fn last_sync_slot_offset(&self) -> usize {
+        self.registration_proof_offset() + 1
+    }
+}
+impl<D: AsRef<[u8]> + AsMut<[u8]>> MintExtensionView<D> {
+    /// Clears account data sized for the latest layout and tags it, ready
+    /// for the fields of a new registration. Any metadata section is lost.
+    pub fn init(mut data: D) -> Result<Self, ProgramError> {
+        let dst = data.as_mut();
+        if dst.len() != MintExtension::account_len(MintExtensionLayout::LATEST) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        dst.fill(0);
+        if let Some(account_type) = MintExtensionLayout::LATEST.account_type() {
+            dst[0] = account_type as u8;
+        }
+        Ok(Self { data, layout: MintExtensionLayout::LATEST })
+    }
+
+    /// Sets mint_authority, which layouts before V3 also read as update_authority.
+    pub fn set_mint_authority(&mut self, mint_authority: &Pubkey) {
+        self.set_pubkey(MINT_AUTHORITY_OFFSET, mint_authority);
+    }
+
+    /// Sets freeze_authority.
+    pub fn set_freeze_authority(&mut self, freeze_authority: &Pubkey) {
+        self.set_pubkey(FREEZE_AUTHORITY_OFFSET, freeze_authority);
+    }
+
+    /// Sets supply.
+    pub fn set_supply(&mut self, supply: u64) {
+        array_mut_ref![self.body_mut(), SUPPLY_OFFSET, 8].copy_from_slice(&supply.to_le_bytes());
+    }
+
+    /// Sets decimals.
+    pub fn set_decimals(&mut self, decimals: u8) {
+        self.body_mut()[DECIMALS_OFFSET] = decimals;
+    }
+
+    /// Sets is_initialized.
+    pub fn set_is_initialized(&mut self, is_initialized: bool) {
+        self.body_mut()[IS_INITIALIZED_OFFSET] = is_initialized as u8;
+    }
+
+    /// Sets mint.
+    pub fn set_mint(&mut self, mint: &Pubkey) {
+        self.set_pubkey(MINT_OFFSET, mint);
+    }
+
+    /// Sets the symbol, zeroing the rest of the field.
+    pub fn set_symbol(&mut self, symbol: &str) -> Result<(), ProgramError> {
+        self.set_str_field(SYMBOL_LEN_OFFSET, symbol)
+    }
+
+    /// Sets the name, zeroing the rest of the field.
+    pub fn set_name(&mut self, name: &str) -> Result<(), ProgramError> {
+        let offset = self.name_len_offset();
+        self.set_str_field(offset, name)
+    }
+
+    /// Sets verified.
+    pub fn set_verified(&mut self, verified: bool) -> Result<(), ProgramError> {
+        if self.layout < MintExtensionLayout::V3 {
+            return if verified { Err(ProgramError::AccountDataTooSmall) } else { Ok(()) };
+        }
+        let offset = self.verified_offset();
+        self.body_mut()[offset] = verified as u8;
+        Ok(())
+    }
+
+    /// Sets update_authority.
+    pub fn set_update_authority(&mut self, update_authority: &Pubkey) -> Result<(), ProgramError> {
+        if self.layout < MintExtensionLayout::V3 {
+            return if *update_authority == self.mint_authority() {
+                Ok(())
+            } else {
+                Err(ProgramError::AccountDataTooSmall)
+            };
+        }
+        let offset = self.update_authority_offset();
+        self.set_pubkey(offset, update_authority);
+        Ok(())
+    }
+
+    /// Sets registration_proof.
+    pub fn set_registration_proof(&mut self, registration_proof: RegistrationProof) -> Result<(), ProgramError> {
+        if self.layout < MintExtensionLayout::V3 {
+            return if registration_proof == RegistrationProof::MintAuthority {
+                Ok(())
+            } else {
+                Err(ProgramError::AccountDataTooSmall)
+            };
+        }
+        let offset = self.registration_proof_offset();
+        self.body_mut()[offset] = registration_proof as u8;
+        Ok(())
+    }
+
+    /// Sets last_sync_slot.
+    pub fn set_last_sync_slot(&mut self, last_sync_slot: u64) -> Result<(), ProgramError> {
+        if self.layout < MintExtensionLayout::V3 {
+            return if last_sync_slot == 0 { Ok(()) } else { Err(ProgramError::AccountDataTooSmall) };
+        }
+        let offset = self.last_sync_slot_offset();
+        array_mut_ref![self.body_mut(), offset, 8].copy_from_slice(&last_sync_slot.to_le_bytes());
+        Ok(())
+    }
+
+    fn body_mut(&mut self) -> &mut [u8] {
+        let data = self.data.as_mut();
+        match self.layout {
+            MintExtensionLayout::V0 => data,
+            _ => &mut data[1..],
+        }
+    }
+
+    fn set_pubkey(&mut self, offset: usize, key: &Pubkey) {
+        array_mut_ref![self.body_mut(), offset, 32].copy_from_slice(key.as_ref());
+    }
+
+    /// Zeroes the symbol and name bytes past their lengths, as
+    /// [MintExtension::clear_padding](struct.MintExtension.html#method.clear_padding) does.
+    pub fn clear_padding(&mut self) {
+        let max_len = self.layout.max_symbol_name_len();
+        let name_len_offset = self.name_len_offset();
+        for len_offset in [SYMBOL_LEN_OFFSET, name_len_offset] {
+            let len = usize::from(self.byte(len_offset)).min(max_len);
+            self.body_mut()[len_offset + 1 + len..len_offset + 1 + max_len].fill(0);
+        }
+    }
+
+    fn set_str_field(&mut self, len_offset: usize, value: &str) -> Result<(), ProgramError> {
+        let max_len = self.layout.max_symbol_name_len();
+        if value.len() > max_len {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        let field = &mut self.body_mut()[len_offset..len_offset + 1 + max_len];
+        field[0] = value.len() as u8;
+        let (bytes, padding) = field[1..].split_at_mut(value.len());
+        bytes.copy_from_slice(value.as_bytes());
+        padding.fill(0);
+        Ok(())
+    }
+}
+
+/// Variable length metadata kept after the MintExtension in the tagged layouts.
+///
+/// The section is only written once a field is set, so accounts without
+/// metadata keep the size of the bare extension.
+#[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct MintMetadata {
+    /// URI of the token logo
+    pub logo_uri: String,
+    /// project website
+    pub website: String,
+    /// short description of the token
+    pub description: String,
+    /// tags such as "stablecoin" or "wrapped"
+    pub tags: Vec<String>,
+}
+impl MintMetadata {
+    /// Size of the metadata section, 0 while every field is empty
+    pub fn section_len(&self) -> usize {
+        if *self == Self::default() {
+            return 0;
+        }
+        borsh::object_length(self).unwrap()
+    }
+
+    /// Size of an account in the latest layout holding this metadata
+    pub fn account_len(&self) -> usize {
+        MintExtension::account_len(MintExtensionLayout::LATEST) + self.section_len()
+    }
+
+    /// Unpacks the metadata section of account data in any layout.
+    ///
+    /// V0 accounts and accounts without a section have empty metadata.
+    pub fn unpack_account(src: &[u8]) -> Result<Self, ProgramError> {
+        let layout = MintExtensionLayout::detect(src)?;
+        if layout == MintExtensionLayout::V0 {
+            return Ok(Self::default());
+        }
+        let mut section = src
+            .get(MintExtension::account_len(layout)..)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        if section.is_empty() {
+            return Ok(Self::default());
+        }
+        let metadata = Self::deserialize(&mut section)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        if !section.is_empty() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(metadata)
+    }
+
+    /// Packs the metadata section of an account in the latest layout, which
+    /// must already have the size given by [account_len](#method.account_len).
+    pub fn pack_account(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() != self.account_len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if self.section_len() > 0 {
+            let mut section = &mut dst[MintExtension::account_len(MintExtensionLayout::LATEST)..];
+            self.serialize(&mut section)
+                .map_err(|_| ProgramError::AccountDataTooSmall)?;
+        }
+        Ok(())
+    }
+}
+
 impl Sealed for MintExtension {}
 impl IsInitialized for MintExtension {
     fn is_initialized(&self) -> bool {
         self.is_initialized
     } 
 }
+/// Claim on a symbol, stored at the address given by
+/// [find_symbol_index_address](../fn.find_symbol_index_address.html).
+///
+/// Only one mint can hold a symbol; the index is closed when the mint
+/// renames or closes its extension.
+#[derive(Clone, Copy, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct SymbolIndex {
+    /// mint holding the symbol
+    pub mint: Pubkey,
+}
+impl SymbolIndex {
+    /// Size of a SymbolIndex account, tag included
+    pub const LEN: usize = 1 + 32;
+
+    /// Unpacks account data, checking its tag.
+    pub fn unpack_account(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN || src[0] != AccountType::SymbolIndex as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Self::deserialize(&mut &src[1..]).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Packs into account data, tag included.
+    pub fn pack_account(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (tag, mut body) = dst.split_at_mut(1);
+        tag[0] = AccountType::SymbolIndex as u8;
+        self.serialize(&mut body)
+            .map_err(|_| ProgramError::AccountDataTooSmall)
+    }
+}
+
+/// Program wide settings, stored at the address given by
+/// [find_registry_config_address](../fn.find_registry_config_address.html).
+#[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct RegistryConfig {
+    /// authority allowed to change the config
+    pub admin: Pubkey,
+    /// admin proposed by the current one, default while no transfer is pending
+    pub pending_admin: Pubkey,
+    /// rejects every instruction but the admin ones while set
+    pub paused: bool,
+    /// lamports paid into the config account by RegisterMint
+    pub registration_fee: u64,
+    /// keys allowed to verify and unverify mints
+    pub curators: Vec<Pubkey>,
+    /// rules applied to the symbols and names of mints
+    pub policy: ValidationPolicy,
+}
+impl RegistryConfig {
+    /// Size of a RegistryConfig account, tag included
+    pub fn account_len(&self) -> usize {
+        1 + borsh::object_length(self).unwrap()
+    }
+
+    /// Tells whether the key is one of the curators.
+    pub fn is_curator(&self, key: &Pubkey) -> bool {
+        self.curators.contains(key)
+    }
+
+    /// Unpacks account data, checking its tag.
+    pub fn unpack_account(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.first() != Some(&(AccountType::RegistryConfig as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        borsh::from_slice(&src[1..]).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Packs into account data, tag included. The account must already have
+    /// the size given by [account_len](#method.account_len).
+    pub fn pack_account(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() != self.account_len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (tag, mut body) = dst.split_at_mut(1);
+        tag[0] = AccountType::RegistryConfig as u8;
+        self.serialize(&mut body)
+            .map_err(|_| ProgramError::AccountDataTooSmall)
+    }
+}
+
+// packed layout of V0 and V1 accounts, with 16 bytes of symbol and name
 impl Pack for MintExtension {
     const LEN: usize = 140;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
@@ -69,12 +785,14 @@ impl Pack for MintExtension {
         let mint_authority = Pubkey::new_from_array(*mint_authority);
         let freeze_authority = Pubkey::new_from_array(*freeze_authority);
         let supply = u64::from_le_bytes(*supply_buf);
-        let decimals = decimals_buf[0] as u8;
+        let decimals = decimals_buf[0];
         let mint = Pubkey::new_from_array(*mint);
-        let symbol_len = symbol_len[0] as u8;
-        let name_len = name_len[0] as u8;
-        let symbol = symbol_buf.clone();
-        let name = name_buf.clone();
+        let symbol_len = symbol_len[0];
+        let name_len = name_len[0];
+        let mut symbol = [0; MAX_SYMBOL_NAME_LEN];
+        symbol[..LEGACY_SYMBOL_NAME_LEN].copy_from_slice(symbol_buf);
+        let mut name = [0; MAX_SYMBOL_NAME_LEN];
+        name[..LEGACY_SYMBOL_NAME_LEN].copy_from_slice(name_buf);
         Ok(MintExtension {
             mint_authority, 
             freeze_authority, 
@@ -86,6 +804,10 @@ impl Pack for MintExtension {
             symbol,
             name_len,
             name,
+            verified: false,
+            update_authority: mint_authority,
+            registration_proof: RegistrationProof::MintAuthority,
+            last_sync_slot: 0,
         })
     }
     fn pack_into_slice(&self, dst: &mut [u8]) {
@@ -113,6 +835,7 @@ impl Pack for MintExtension {
             ref symbol,
             name_len,
             ref name,
+            ..
         } = self;
         mint_authority_dst.copy_from_slice(mint_authority.as_ref());
         freeze_authority_dst.copy_from_slice(freeze_authority.as_ref());
@@ -121,9 +844,9 @@ impl Pack for MintExtension {
         is_initialized_dst[0] = is_initialized as u8;
         mint_dst.copy_from_slice(mint.as_ref());
         symbol_len_dst[0] = symbol_len;
-        symbol_dst.copy_from_slice(&symbol[0..]);
+        symbol_dst.copy_from_slice(&symbol[..LEGACY_SYMBOL_NAME_LEN]);
         name_len_dst[0] = name_len;
-        name_dst.copy_from_slice(&name[0..]);
+        name_dst.copy_from_slice(&name[..LEGACY_SYMBOL_NAME_LEN]);
     }
 }
 
@@ -198,6 +921,63 @@ impl Pack for Mint {
     }
 }
 
+/// Maximum number of multisignature signers (max N)
+pub const MAX_SIGNERS: usize = 11;
+
+/// Multisignature data.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Multisig {
+    /// Number of signers required
+    pub m: u8,
+    /// Number of valid signers
+    pub n: u8,
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+    /// Signer public keys
+    pub signers: [Pubkey; MAX_SIGNERS],
+}
+impl Sealed for Multisig {}
+impl IsInitialized for Multisig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for Multisig {
+    const LEN: usize = 355;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 355];
+        #[allow(clippy::ptr_offset_with_cast)]
+        let (m, n, is_initialized, signers_flat) = array_refs![src, 1, 1, 1, 32 * MAX_SIGNERS];
+        let mut result = Multisig {
+            m: m[0],
+            n: n[0],
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            signers: [Pubkey::default(); MAX_SIGNERS],
+        };
+        for (i, dst) in result.signers.iter_mut().enumerate() {
+            *dst = Pubkey::new_from_array(*array_ref![signers_flat, 32 * i, 32]);
+        }
+        Ok(result)
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 355];
+        #[allow(clippy::ptr_offset_with_cast)]
+        let (m, n, is_initialized, signers_flat) = mut_array_refs![dst, 1, 1, 1, 32 * MAX_SIGNERS];
+        *m = [self.m];
+        *n = [self.n];
+        *is_initialized = [self.is_initialized as u8];
+        for (i, src) in self.signers.iter().enumerate() {
+            let dst_array = array_mut_ref![signers_flat, 32 * i, 32];
+            dst_array.copy_from_slice(src.as_ref());
+        }
+    }
+}
+
 
 // Helpers
 fn pack_coption_key(src: &COption<Pubkey>, dst: &mut [u8; 36]) {
@@ -212,6 +992,17 @@ fn pack_coption_key(src: &COption<Pubkey>, dst: &mut [u8; 36]) {
         }
     }
 }
+fn check_padding(src: &[u8], len: u8) -> Result<(), ProgramError> {
+    match src.get(usize::from(len)..) {
+        Some(padding) if padding.iter().all(|&byte| byte == 0) => Ok(()),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_str(src: &[u8], len: u8) -> Result<&str, ProgramError> {
+    let bytes = src.get(..usize::from(len)).ok_or(ProgramError::InvalidAccountData)?;
+    std::str::from_utf8(bytes).map_err(|_| ProgramError::InvalidAccountData)
+}
 fn unpack_coption_key(src: &[u8; 36]) -> Result<COption<Pubkey>, ProgramError> {
     let (tag, body) = array_refs![src, 4, 32];
     match *tag {
@@ -219,4 +1010,397 @@ fn unpack_coption_key(src: &[u8; 36]) -> Result<COption<Pubkey>, ProgramError> {
         [1, 0, 0, 0] => Ok(COption::Some(Pubkey::new_from_array(*body))),
         _ => Err(ProgramError::InvalidAccountData),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn padded(value: &str) -> [u8; MAX_SYMBOL_NAME_LEN] {
+        let mut buf = [0; MAX_SYMBOL_NAME_LEN];
+        buf[..value.len()].copy_from_slice(value.as_bytes());
+        buf
+    }
+
+    #[test]
+    fn test_mint_extension_layouts() {
+        let mint_ext = MintExtension {
+            mint_authority: Pubkey::new_unique(),
+            freeze_authority: Pubkey::new_unique(),
+            supply: 42,
+            decimals: 9,
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            symbol_len: 3,
+            symbol: padded("SYM"),
+            name_len: 4,
+            name: padded("name"),
+            verified: false,
+            update_authority: Pubkey::default(),
+            registration_proof: RegistrationProof::MintAuthority,
+            last_sync_slot: 0,
+        };
+        let mint_ext = MintExtension { update_authority: mint_ext.mint_authority, ..mint_ext };
+        assert_eq!(
+            borsh::to_vec(&mint_ext).unwrap().len() + 1 + MintExtension::RESERVED_LEN,
+            MintExtension::V3_LEN
+        );
+        assert_eq!(MintExtensionLayout::ALL.last(), Some(&MintExtensionLayout::LATEST));
+
+        for &layout in &MintExtensionLayout::ALL {
+            let mut data = vec![0; MintExtension::account_len(layout)];
+            mint_ext.pack_account(layout, &mut data).unwrap();
+            assert_eq!(MintExtensionLayout::detect(&data).unwrap(), layout);
+            assert_eq!(MintExtension::unpack_account(&data).unwrap(), (mint_ext, layout));
+        }
+
+        let mut data = vec![0; MintExtension::V1_LEN];
+        mint_ext.pack_account(MintExtensionLayout::V1, &mut data).unwrap();
+        assert_eq!(data[0], AccountType::MintExtensionV1 as u8);
+        assert_eq!(&data[1..], &{
+            let mut v0 = vec![0; MintExtension::LEN];
+            mint_ext.pack_into_slice(&mut v0);
+            v0
+        }[..]);
+
+        let mut data = vec![0; MintExtension::V2_LEN];
+        mint_ext.pack_account(MintExtensionLayout::V2, &mut data).unwrap();
+        assert_eq!(data[0], AccountType::MintExtensionV2 as u8);
+        let mut v3 = vec![0; MintExtension::V3_LEN];
+        mint_ext.pack_account(MintExtensionLayout::V3, &mut v3).unwrap();
+        assert_eq!(v3[0], AccountType::MintExtensionV3 as u8);
+        assert_eq!(&v3[1..MintExtension::V2_LEN], &data[1..]);
+
+        // older layouts have none of the registry fields
+        let verified = MintExtension { verified: true, ..mint_ext };
+        let delegated = MintExtension { update_authority: Pubkey::new_unique(), ..mint_ext };
+        let revoked = MintExtension { update_authority: Pubkey::default(), ..mint_ext };
+        let attested = MintExtension { registration_proof: RegistrationProof::AdminAttestation, ..mint_ext };
+        let synced = MintExtension { last_sync_slot: 1234, ..mint_ext };
+        for changed in [verified, delegated, revoked, attested, synced] {
+            assert_eq!(
+                changed.pack_account(MintExtensionLayout::V2, &mut data),
+                Err(ProgramError::AccountDataTooSmall)
+            );
+            changed.pack_account(MintExtensionLayout::V3, &mut v3).unwrap();
+            assert_eq!(MintExtension::unpack_account(&v3).unwrap(), (changed, MintExtensionLayout::V3));
+        }
+        let fields_end = MintExtension::V3_LEN - MintExtension::RESERVED_LEN;
+        assert_eq!(v3[fields_end - 8..fields_end], 1234u64.to_le_bytes());
+        assert_eq!(v3[fields_end - 9], RegistrationProof::MintAuthority as u8);
+
+        // the reserved bytes are written as zero and ignored when read
+        assert!(v3[fields_end..].iter().all(|&byte| byte == 0));
+        v3[fields_end..].fill(0xff);
+        assert_eq!(MintExtension::unpack_account(&v3).unwrap(), (synced, MintExtensionLayout::V3));
+        synced.pack_account(MintExtensionLayout::V3, &mut v3).unwrap();
+        assert!(v3[fields_end..].iter().all(|&byte| byte == 0));
+
+        let mut data = vec![0; MintExtension::V1_LEN];
+        assert_eq!(MintExtension::unpack_account(&data), Err(ProgramError::InvalidAccountData));
+        assert_eq!(
+            mint_ext.pack_account(MintExtensionLayout::V0, &mut data),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_mint_extension_view() {
+        let mint_ext = MintExtension {
+            mint_authority: Pubkey::new_unique(),
+            freeze_authority: Pubkey::new_unique(),
+            supply: 1_000_000,
+            decimals: 6,
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            symbol_len: 4,
+            symbol: padded("USDC"),
+            name_len: 8,
+            name: padded("USD Coin"),
+            ..MintExtension::default()
+        };
+        let new_authority = Pubkey::new_unique();
+        for &layout in &MintExtensionLayout::ALL {
+            let mint_ext = MintExtension { update_authority: mint_ext.mint_authority, ..mint_ext };
+            let mut data = vec![0; MintExtension::account_len(layout)];
+            mint_ext.pack_account(layout, &mut data).unwrap();
+
+            let view = MintExtensionView::new(&data[..]).unwrap();
+            assert_eq!(view.layout(), layout);
+            assert_eq!(view.mint_authority(), mint_ext.mint_authority);
+            assert_eq!(view.freeze_authority(), mint_ext.freeze_authority);
+            assert_eq!(view.supply(), 1_000_000);
+            assert_eq!(view.decimals(), 6);
+            assert!(view.is_initialized());
+            assert_eq!(view.mint(), mint_ext.mint);
+            assert_eq!(view.symbol().unwrap(), "USDC");
+            assert_eq!(view.name().unwrap(), "USD Coin");
+            assert!(!view.verified());
+            assert_eq!(view.update_authority(), mint_ext.mint_authority);
+            assert_eq!(view.registration_proof(), RegistrationProof::MintAuthority);
+            assert_eq!(view.last_sync_slot(), 0);
+
+            // setters write what unpack_account reads back
+            let mut view = MintExtensionView::new(&mut data[..]).unwrap();
+            view.set_supply(42);
+            view.set_is_initialized(false);
+            view.set_symbol("UC").unwrap();
+            view.set_name("Coin").unwrap();
+            view.set_verified(false).unwrap();
+            assert_eq!(view.set_symbol(&"X".repeat(layout.max_symbol_name_len() + 1)), Err(ProgramError::AccountDataTooSmall));
+            let expected = MintExtension {
+                supply: 42,
+                is_initialized: false,
+                symbol_len: 2,
+                symbol: padded("UC"),
+                name_len: 4,
+                name: padded("Coin"),
+                ..mint_ext
+            };
+            assert_eq!(MintExtension::unpack_account(&data).unwrap(), (expected, layout));
+
+            // fields the layout predates only take the value they read back as
+            let mut view = MintExtensionView::new(&mut data[..]).unwrap();
+            assert_eq!(view.set_verified(true).is_ok(), layout >= MintExtensionLayout::V3);
+            assert_eq!(view.set_update_authority(&new_authority).is_ok(), layout >= MintExtensionLayout::V3);
+            assert_eq!(
+                view.set_registration_proof(RegistrationProof::FreezeAuthority).is_ok(),
+                layout >= MintExtensionLayout::V3
+            );
+            assert_eq!(view.set_last_sync_slot(1234).is_ok(), layout >= MintExtensionLayout::V3);
+            let (unpacked, _) = MintExtension::unpack_account(&data).unwrap();
+            assert_eq!(unpacked.verified, layout >= MintExtensionLayout::V3);
+            assert_eq!(unpacked.update_authority == new_authority, layout >= MintExtensionLayout::V3);
+            assert_eq!(
+                unpacked.registration_proof == RegistrationProof::FreezeAuthority,
+                layout >= MintExtensionLayout::V3
+            );
+            assert_eq!(unpacked.last_sync_slot == 1234, layout >= MintExtensionLayout::V3);
+        }
+
+        // malformed flags are rejected as by unpack_account
+        let mut data = vec![0; MintExtension::account_len(MintExtensionLayout::LATEST)];
+        mint_ext.pack_account(MintExtensionLayout::LATEST, &mut data).unwrap();
+        data[1 + 73] = 2;
+        assert!(MintExtension::unpack_account(&data).is_err());
+        assert_eq!(MintExtensionView::new(&data[..]).err(), Some(ProgramError::InvalidAccountData));
+        assert_eq!(MintExtensionView::new(&data[..10]).err(), Some(ProgramError::InvalidAccountData));
+
+        // init clears the account for a new registration
+        let mut view = MintExtensionView::init(&mut data[..]).unwrap();
+        view.set_mint(&mint_ext.mint);
+        view.set_is_initialized(true);
+        assert_eq!(
+            MintExtension::unpack_account(&data).unwrap(),
+            (
+                MintExtension { mint: mint_ext.mint, is_initialized: true, ..MintExtension::default() },
+                MintExtensionLayout::LATEST
+            )
+        );
+        data.push(0);
+        assert_eq!(MintExtensionView::init(&mut data[..]).err(), Some(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_mint_extension_invariants() {
+        let mut mint_ext = MintExtension {
+            symbol_len: 3,
+            symbol: padded("SYM"),
+            name_len: 5,
+            name: padded("short"),
+            ..MintExtension::default()
+        };
+        assert_eq!(mint_ext.check_invariants(), Ok(()));
+
+        // the tail of a longer name left behind
+        mint_ext.name = padded("short and stale");
+        assert_eq!(mint_ext.check_invariants(), Err(ProgramError::InvalidAccountData));
+        let mut data = vec![0; MintExtension::account_len(MintExtensionLayout::LATEST)];
+        mint_ext.pack_account(MintExtensionLayout::LATEST, &mut data).unwrap();
+        assert_eq!(
+            MintExtensionView::new(&data[..]).unwrap().check_invariants(),
+            Err(ProgramError::InvalidAccountData)
+        );
+        mint_ext.clear_padding();
+        assert_eq!(mint_ext.name, padded("short"));
+        assert_eq!(mint_ext.check_invariants(), Ok(()));
+
+        // the view rewrites whole fields, or clears the padding in place
+        let mut clean = data.clone();
+        let mut view = MintExtensionView::new(&mut data[..]).unwrap();
+        view.set_name("short").unwrap();
+        assert_eq!(view.check_invariants(), Ok(()));
+        assert_eq!(MintExtension::unpack_account(&data).unwrap().0, mint_ext);
+        let mut view = MintExtensionView::new(&mut clean[..]).unwrap();
+        view.clear_padding();
+        assert_eq!(view.check_invariants(), Ok(()));
+        assert_eq!(clean, data);
+
+        // lengths past the field
+        let too_long = MintExtension { symbol_len: MAX_SYMBOL_NAME_LEN as u8 + 1, ..mint_ext };
+        assert_eq!(too_long.check_invariants(), Err(ProgramError::InvalidAccountData));
+        let mut data = vec![0; MintExtension::V1_LEN];
+        mint_ext.pack_account(MintExtensionLayout::V1, &mut data).unwrap();
+        assert_eq!(MintExtensionView::new(&data[..]).unwrap().check_invariants(), Ok(()));
+        data[1 + 106] = LEGACY_SYMBOL_NAME_LEN as u8 + 1;
+        assert_eq!(
+            MintExtensionView::new(&data[..]).unwrap().check_invariants(),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_long_symbol_name() {
+        let name = "Wrapped Ethereum (Wormhole)";
+        let mint_ext = MintExtension {
+            is_initialized: true,
+            symbol_len: 16,
+            symbol: padded("SIXTEEN_BYTES_OK"),
+            name_len: name.len() as u8,
+            name: padded(name),
+            ..MintExtension::default()
+        };
+        assert_eq!(mint_ext.symbol().unwrap(), "SIXTEEN_BYTES_OK");
+        assert_eq!(mint_ext.name().unwrap(), name);
+
+        let mut data = vec![0; MintExtension::V2_LEN];
+        mint_ext.pack_account(MintExtensionLayout::V2, &mut data).unwrap();
+        assert_eq!(MintExtension::unpack_account(&data).unwrap().0.name().unwrap(), name);
+
+        // the legacy layouts only hold 16 bytes
+        let mut data = vec![0; MintExtension::V1_LEN];
+        assert_eq!(
+            mint_ext.pack_account(MintExtensionLayout::V1, &mut data),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+        let short = MintExtension { name_len: 16, ..mint_ext };
+        short.pack_account(MintExtensionLayout::V1, &mut data).unwrap();
+        assert_eq!(MintExtension::unpack_account(&data).unwrap().0.name().unwrap(), &name[..16]);
+
+        // a length cutting a character in half is rejected
+        let cut = MintExtension {
+            name_len: 2,
+            name: padded("\u{e9}t\u{e9}"),
+            ..mint_ext
+        };
+        assert_eq!(cut.name(), Ok("\u{e9}"));
+        let cut = MintExtension { name_len: 1, ..cut };
+        assert_eq!(cut.name(), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_symbol_index() {
+        let index = SymbolIndex { mint: Pubkey::new_unique() };
+        let mut data = vec![0; SymbolIndex::LEN];
+        index.pack_account(&mut data).unwrap();
+        assert_eq!(data[0], AccountType::SymbolIndex as u8);
+        assert_eq!(SymbolIndex::unpack_account(&data).unwrap(), index);
+
+        data[0] = AccountType::MintExtensionV2 as u8;
+        assert_eq!(SymbolIndex::unpack_account(&data), Err(ProgramError::InvalidAccountData));
+        assert_eq!(SymbolIndex::unpack_account(&data[1..]), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_registry_config() {
+        let config = RegistryConfig {
+            admin: Pubkey::new_unique(),
+            pending_admin: Pubkey::new_unique(),
+            paused: true,
+            registration_fee: 42,
+            curators: vec![],
+            policy: ValidationPolicy::default(),
+        };
+        let policy_len = borsh::object_length(&config.policy).unwrap();
+        assert_eq!(config.account_len(), 1 + 32 + 32 + 1 + 8 + 4 + policy_len);
+        let mut data = vec![0; config.account_len()];
+        config.pack_account(&mut data).unwrap();
+        assert_eq!(data[0], AccountType::RegistryConfig as u8);
+        assert_eq!(RegistryConfig::unpack_account(&data).unwrap(), config);
+
+        let curator = Pubkey::new_unique();
+        let curated = RegistryConfig { curators: vec![curator], ..config.clone() };
+        assert_eq!(curated.account_len(), config.account_len() + 32);
+        assert_eq!(curated.pack_account(&mut data), Err(ProgramError::InvalidAccountData));
+        data.resize(curated.account_len(), 0);
+        curated.pack_account(&mut data).unwrap();
+        let unpacked = RegistryConfig::unpack_account(&data).unwrap();
+        assert!(unpacked.is_curator(&curator));
+        assert!(!unpacked.is_curator(&config.admin));
+
+        data.push(0);
+        assert_eq!(RegistryConfig::unpack_account(&data), Err(ProgramError::InvalidAccountData));
+        data.pop();
+
+        data[0] = AccountType::SymbolIndex as u8;
+        assert_eq!(RegistryConfig::unpack_account(&data), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_mint_metadata_section() {
+        let mint_ext = MintExtension {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            ..MintExtension::default()
+        };
+        let mut data = vec![0; MintExtension::account_len(MintExtensionLayout::LATEST)];
+        mint_ext.pack_account(MintExtensionLayout::LATEST, &mut data).unwrap();
+        assert_eq!(MintMetadata::default().account_len(), MintExtension::account_len(MintExtensionLayout::LATEST));
+        assert_eq!(MintMetadata::unpack_account(&data).unwrap(), MintMetadata::default());
+
+        let metadata = MintMetadata {
+            logo_uri: String::from("https://example.com/logo.png"),
+            website: String::from("https://example.com"),
+            description: String::from("an example token"),
+            tags: vec![String::from("stablecoin"), String::from("wrapped")],
+        };
+        data.resize(metadata.account_len(), 0);
+        metadata.pack_account(&mut data).unwrap();
+        assert_eq!(MintMetadata::unpack_account(&data).unwrap(), metadata);
+        assert_eq!(MintExtension::unpack_account(&data).unwrap(), (mint_ext, MintExtensionLayout::LATEST));
+
+        // the extension can be rewritten without touching the section
+        let modified = MintExtension { decimals: 6, ..mint_ext };
+        modified.pack_account(MintExtensionLayout::LATEST, &mut data).unwrap();
+        assert_eq!(MintMetadata::unpack_account(&data).unwrap(), metadata);
+
+        data.push(0);
+        assert_eq!(MintMetadata::unpack_account(&data), Err(ProgramError::InvalidAccountData));
+        assert_eq!(metadata.pack_account(&mut data), Err(ProgramError::InvalidAccountData));
+        data.truncate(data.len() - 2);
+        assert_eq!(MintMetadata::unpack_account(&data), Err(ProgramError::InvalidAccountData));
+
+        let mut data = vec![0; MintExtension::LEN];
+        mint_ext.pack_account(MintExtensionLayout::V0, &mut data).unwrap();
+        assert_eq!(MintMetadata::unpack_account(&data).unwrap(), MintMetadata::default());
+
+        // V1 accounts keep their section right after the shorter extension
+        let mut data = vec![0; MintExtension::V1_LEN];
+        mint_ext.pack_account(MintExtensionLayout::V1, &mut data).unwrap();
+        data.extend_from_slice(&borsh::to_vec(&metadata).unwrap());
+        assert_eq!(MintMetadata::unpack_account(&data).unwrap(), metadata);
+    }
+
+    #[test]
+    fn test_multisig() {
+        let mut multisig = Multisig {
+            m: 2,
+            n: 3,
+            is_initialized: true,
+            ..Multisig::default()
+        };
+        for signer in multisig.signers.iter_mut().take(3) {
+            *signer = Pubkey::new_unique();
+        }
+        let mut data = vec![0; Multisig::LEN];
+        Multisig::pack(multisig, &mut data).unwrap();
+        assert_eq!(data[..3], [2, 3, 1]);
+        assert_eq!(data[3..35], multisig.signers[0].to_bytes());
+        assert_eq!(Multisig::unpack(&data).unwrap(), multisig);
+
+        data[2] = 2;
+        assert_eq!(Multisig::unpack(&data), Err(ProgramError::InvalidAccountData));
+        data[2] = 0;
+        assert_eq!(Multisig::unpack(&data), Err(ProgramError::UninitializedAccount));
+    }
+}
@@ -0,0 +1,280 @@
+//! Symbol and name validation policy
+
+use crate::{error::RegistryError, state::MAX_SYMBOL_NAME_LEN};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// maximum number of reserved symbols in a policy
+pub const MAX_RESERVED_SYMBOLS: usize = 32;
+/// maximum length in bytes of the punctuation accepted by a charset
+pub const MAX_PUNCTUATION_LEN: usize = 32;
+
+/// Characters accepted in a symbol or name.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct Charset {
+    /// accept letters and digits outside ASCII
+    pub unicode_alphanumeric: bool,
+    /// punctuation accepted besides letters and digits
+    pub punctuation: String,
+    /// accept single spaces between words
+    pub inner_space: bool,
+}
+impl Charset {
+    /// ASCII letters, digits and a few separators
+    pub fn symbol() -> Self {
+        Self {
+            unicode_alphanumeric: false,
+            punctuation: String::from("-._$"),
+            inner_space: false,
+        }
+    }
+
+    /// Letters and digits of any script, punctuation and single spaces
+    pub fn name() -> Self {
+        Self {
+            unicode_alphanumeric: true,
+            punctuation: String::from("-_.,:;'&()/+$#!?"),
+            inner_space: true,
+        }
+    }
+
+    /// Tells whether a character other than whitespace is accepted.
+    pub fn contains(&self, c: char) -> bool {
+        c.is_ascii_alphanumeric()
+            || (self.unicode_alphanumeric && c.is_alphanumeric())
+            || self.punctuation.contains(c)
+    }
+}
+
+/// What to do with leading and trailing whitespace.
+#[derive(Clone, Copy, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum Whitespace {
+    /// fail with UnexpectedWhitespace
+    Reject,
+    /// strip it before the value is checked and stored
+    Trim,
+}
+
+/// Rules applied to symbols and names by RegisterMint and ModifyMint.
+///
+/// The processor reads it from the
+/// [RegistryConfig](../state/struct.RegistryConfig.html), where the admin
+/// sets it with SetValidationPolicy.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct ValidationPolicy {
+    /// characters accepted in a symbol
+    pub symbol_charset: Charset,
+    /// leading and trailing whitespace in a symbol
+    pub symbol_whitespace: Whitespace,
+    /// characters accepted in a name
+    pub name_charset: Charset,
+    /// leading and trailing whitespace in a name
+    pub name_whitespace: Whitespace,
+    /// symbols nobody may register, compared after [normalize_symbol](fn.normalize_symbol.html)
+    pub reserved_symbols: Vec<String>,
+    /// longest symbol accepted, in bytes
+    pub max_symbol_len: u8,
+    /// longest name accepted, in bytes
+    pub max_name_len: u8,
+}
+impl Default for ValidationPolicy {
+    /// Policy enforced by the processor unless told otherwise
+    fn default() -> Self {
+        Self {
+            symbol_charset: Charset::symbol(),
+            symbol_whitespace: Whitespace::Reject,
+            name_charset: Charset::name(),
+            name_whitespace: Whitespace::Trim,
+            reserved_symbols: ["SOL", "WSOL", "NATIVE", "NONE", "NULL", "UNKNOWN"]
+                .iter()
+                .map(|symbol| String::from(*symbol))
+                .collect(),
+            max_symbol_len: MAX_SYMBOL_NAME_LEN as u8,
+            max_name_len: MAX_SYMBOL_NAME_LEN as u8,
+        }
+    }
+}
+impl ValidationPolicy {
+    /// Checks that the policy fits the limits of the program: lengths within
+    /// [MAX_SYMBOL_NAME_LEN](../state/constant.MAX_SYMBOL_NAME_LEN.html),
+    /// at most [MAX_RESERVED_SYMBOLS](constant.MAX_RESERVED_SYMBOLS.html)
+    /// reserved symbols and [MAX_PUNCTUATION_LEN](constant.MAX_PUNCTUATION_LEN.html)
+    /// bytes of punctuation per charset.
+    pub fn check_limits(&self) -> Result<(), RegistryError> {
+        if usize::from(self.max_symbol_len) > MAX_SYMBOL_NAME_LEN
+            || usize::from(self.max_name_len) > MAX_SYMBOL_NAME_LEN
+            || self.reserved_symbols.len() > MAX_RESERVED_SYMBOLS
+            || self.reserved_symbols.iter().any(|symbol| symbol.len() > MAX_SYMBOL_NAME_LEN)
+            || self.symbol_charset.punctuation.len() > MAX_PUNCTUATION_LEN
+            || self.name_charset.punctuation.len() > MAX_PUNCTUATION_LEN
+        {
+            return Err(RegistryError::InvalidValidationPolicy);
+        }
+        Ok(())
+    }
+
+
+    /// Validates a symbol, returning the value to store.
+    pub fn validate_symbol(&self, symbol: &str) -> Result<String, RegistryError> {
+        let symbol = check(symbol, &self.symbol_charset, self.symbol_whitespace)?;
+        if symbol.is_empty() {
+            return Err(RegistryError::EmptySymbol);
+        }
+        if symbol.len() > usize::from(self.max_symbol_len) {
+            return Err(RegistryError::SymbolToLong);
+        }
+        let normalized = normalize_symbol(self, symbol);
+        if self.reserved_symbols.iter().any(|reserved| normalize_symbol(self, reserved) == normalized) {
+            return Err(RegistryError::ReservedSymbol);
+        }
+        Ok(String::from(symbol))
+    }
+
+    /// Validates a name, returning the value to store.
+    pub fn validate_name(&self, name: &str) -> Result<String, RegistryError> {
+        let name = check(name, &self.name_charset, self.name_whitespace)?;
+        if name.len() > usize::from(self.max_name_len) {
+            return Err(RegistryError::SymbolToLong);
+        }
+        Ok(String::from(name))
+    }
+}
+
+/// Form of a symbol used for comparisons, so that "usdc" and "USDC" are the same symbol.
+///
+/// Surrounding whitespace is stripped when the policy trims symbols, so a
+/// client deriving the [SymbolIndex](../state/struct.SymbolIndex.html) of
+/// the symbol it sends gets the one the program claims for the stored value.
+pub fn normalize_symbol(policy: &ValidationPolicy, symbol: &str) -> String {
+    match policy.symbol_whitespace {
+        Whitespace::Reject => symbol.to_uppercase(),
+        Whitespace::Trim => symbol.trim().to_uppercase(),
+    }
+}
+
+fn check<'a>(value: &'a str, charset: &Charset, whitespace: Whitespace) -> Result<&'a str, RegistryError> {
+    let trimmed = value.trim();
+    if whitespace == Whitespace::Reject && trimmed != value {
+        return Err(RegistryError::UnexpectedWhitespace);
+    }
+    let mut previous_space = false;
+    for c in trimmed.chars() {
+        if c.is_whitespace() {
+            if !charset.inner_space || c != ' ' || previous_space {
+                return Err(RegistryError::UnexpectedWhitespace);
+            }
+            previous_space = true;
+            continue;
+        }
+        previous_space = false;
+        if !charset.contains(c) {
+            return Err(RegistryError::InvalidCharacter);
+        }
+    }
+    Ok(trimmed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_symbol() {
+        let policy = ValidationPolicy::default();
+        assert_eq!(policy.validate_symbol("USDC"), Ok(String::from("USDC")));
+        assert_eq!(policy.validate_symbol("soBTC-2.0"), Ok(String::from("soBTC-2.0")));
+
+        assert_eq!(policy.validate_symbol("USDC "), Err(RegistryError::UnexpectedWhitespace));
+        assert_eq!(policy.validate_symbol(" USDC"), Err(RegistryError::UnexpectedWhitespace));
+        assert_eq!(policy.validate_symbol("US DC"), Err(RegistryError::UnexpectedWhitespace));
+        assert_eq!(policy.validate_symbol("USD\tC"), Err(RegistryError::UnexpectedWhitespace));
+        assert_eq!(policy.validate_symbol("USD\u{0}C"), Err(RegistryError::InvalidCharacter));
+        assert_eq!(policy.validate_symbol("MOON\u{1F680}"), Err(RegistryError::InvalidCharacter));
+        assert_eq!(policy.validate_symbol("\u{422}\u{41E}\u{41D}"), Err(RegistryError::InvalidCharacter));
+        assert_eq!(policy.validate_symbol(""), Err(RegistryError::EmptySymbol));
+
+        assert_eq!(policy.validate_symbol("SOL"), Err(RegistryError::ReservedSymbol));
+        assert_eq!(policy.validate_symbol("wSol"), Err(RegistryError::ReservedSymbol));
+        assert_eq!(normalize_symbol(&policy, "usdc"), normalize_symbol(&policy, "USDC"));
+        assert_ne!(normalize_symbol(&policy, " USDC"), normalize_symbol(&policy, "USDC"));
+    }
+
+    #[test]
+    fn test_validate_name() {
+        let policy = ValidationPolicy::default();
+        assert_eq!(
+            policy.validate_name("Wrapped Ethereum (Wormhole)"),
+            Ok(String::from("Wrapped Ethereum (Wormhole)"))
+        );
+        assert_eq!(policy.validate_name("  USD Coin "), Ok(String::from("USD Coin")));
+        assert_eq!(policy.validate_name("\u{e9}t\u{e9}"), Ok(String::from("\u{e9}t\u{e9}")));
+        assert_eq!(policy.validate_name(""), Ok(String::new()));
+
+        assert_eq!(policy.validate_name("USD  Coin"), Err(RegistryError::UnexpectedWhitespace));
+        assert_eq!(policy.validate_name("USD\nCoin"), Err(RegistryError::UnexpectedWhitespace));
+        assert_eq!(policy.validate_name("USD Coin\u{7}"), Err(RegistryError::InvalidCharacter));
+        assert_eq!(policy.validate_name("Rocket \u{1F680}"), Err(RegistryError::InvalidCharacter));
+
+        let strict = ValidationPolicy {
+            name_whitespace: Whitespace::Reject,
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(strict.validate_name("USD Coin "), Err(RegistryError::UnexpectedWhitespace));
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = ValidationPolicy {
+            symbol_charset: Charset {
+                punctuation: String::from("-"),
+                ..Charset::symbol()
+            },
+            reserved_symbols: vec![String::from("usdc")],
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(ValidationPolicy::default().validate_symbol("USDC"), Ok(String::from("USDC")));
+        assert_eq!(policy.validate_symbol("USDC"), Err(RegistryError::ReservedSymbol));
+        assert_eq!(ValidationPolicy::default().validate_symbol("soBTC.2"), Ok(String::from("soBTC.2")));
+        assert_eq!(policy.validate_symbol("soBTC.2"), Err(RegistryError::InvalidCharacter));
+        assert_eq!(policy.validate_symbol("soBTC-2"), Ok(String::from("soBTC-2")));
+        // SOL is only reserved by the default policy
+        assert_eq!(policy.validate_symbol("SOL"), Ok(String::from("SOL")));
+
+        let trim = ValidationPolicy {
+            symbol_whitespace: Whitespace::Trim,
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(trim.validate_symbol(" USDC "), Ok(String::from("USDC")));
+        assert_eq!(trim.validate_symbol(" sol "), Err(RegistryError::ReservedSymbol));
+        assert_eq!(normalize_symbol(&trim, " usdc "), normalize_symbol(&trim, "USDC"));
+
+        let short = ValidationPolicy {
+            max_symbol_len: 4,
+            max_name_len: 8,
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(short.validate_symbol("USDC"), Ok(String::from("USDC")));
+        assert_eq!(short.validate_symbol("USDCX"), Err(RegistryError::SymbolToLong));
+        assert_eq!(short.validate_name(" USD Coin "), Ok(String::from("USD Coin")));
+        assert_eq!(short.validate_name("USD Coins"), Err(RegistryError::SymbolToLong));
+    }
+
+    #[test]
+    fn test_check_limits() {
+        assert_eq!(ValidationPolicy::default().check_limits(), Ok(()));
+        let too_long = ValidationPolicy {
+            max_symbol_len: MAX_SYMBOL_NAME_LEN as u8 + 1,
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(too_long.check_limits(), Err(RegistryError::InvalidValidationPolicy));
+        let too_many = ValidationPolicy {
+            reserved_symbols: vec![String::from("SOL"); MAX_RESERVED_SYMBOLS + 1],
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(too_many.check_limits(), Err(RegistryError::InvalidValidationPolicy));
+        let long_reserved = ValidationPolicy {
+            reserved_symbols: vec!["S".repeat(MAX_SYMBOL_NAME_LEN + 1)],
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(long_reserved.check_limits(), Err(RegistryError::InvalidValidationPolicy));
+    }
+}